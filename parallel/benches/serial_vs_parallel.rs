@@ -0,0 +1,346 @@
+//! 对比串行与并行实现在不同输入规模、线程数和负载下的性能。
+//!
+//! 运行方式：`cargo bench -p parallel --bench serial_vs_parallel [-- 负载名过滤]`
+//!
+//! 每个用例先预热，再采集若干个样本；单次运行太短时一个样本会连续运行多次取平均，
+//! 以避免计时器精度的影响。报告中位数及其 95% 置信区间（基于次序统计量，不假设正态分布），
+//! 只有串行与并行的置信区间不重叠时才认为加速比显著。
+//!
+//! 结果同时写入 JSON 报告，便于长期跟踪。可以通过环境变量调整：
+//!
+//! * `PARALLEL_BENCH_MAX_EXP` - 最大输入规模为 10^n，默认 7；
+//! * `PARALLEL_BENCH_THREADS` - 逗号分隔的线程数列表，默认为 1、2、4…直到 CPU 核数；
+//! * `PARALLEL_BENCH_SAMPLES` - 每个用例的样本数，默认 15；
+//! * `PARALLEL_BENCH_REPORT` - JSON 报告的路径，默认 `target/bench-reports/parallel.json`。
+use std::{
+    env, fs,
+    hint::black_box,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use parallel::{
+    par_count_where, par_map, par_partition, par_position, par_simd_change_arr_ele,
+    parallel_any_condition, parallel_change_arr_ele, serial_change_arr_ele, serial_map,
+};
+use serde::Serialize;
+
+/// 预热的最短时间。
+const WARM_UP: Duration = Duration::from_millis(100);
+
+/// 单个样本的最短时间，运行一次不足该时间时在一个样本内重复运行。
+const MIN_SAMPLE: Duration = Duration::from_millis(2);
+
+/// 一种负载：同一个任务的串行和并行实现。
+struct Workload {
+    name: &'static str,
+    /// 该负载测试的最大规模（10 的幂），用于限制计算量大的负载
+    max_exp: u32,
+    serial: fn(&mut Vec<i32>),
+    parallel: fn(&mut Vec<i32>),
+}
+
+/// 计算量较大的单元素操作，用于观察并行在计算密集型任务上的收益。
+fn heavy(x: &i32) -> f64 {
+    (0..8).fold(*x as f64, |acc, i| (acc + i as f64).sqrt().sin() * 100.0)
+}
+
+fn workloads() -> Vec<Workload> {
+    vec![
+        Workload {
+            name: "decrement",
+            max_exp: u32::MAX,
+            serial: |v| serial_change_arr_ele(v),
+            parallel: |v| parallel_change_arr_ele(v),
+        },
+        Workload {
+            name: "decrement_simd",
+            max_exp: u32::MAX,
+            serial: |v| serial_change_arr_ele(v),
+            parallel: |v| par_simd_change_arr_ele(v),
+        },
+        Workload {
+            name: "map_heavy",
+            max_exp: 6,
+            serial: |v| {
+                black_box(serial_map(v, heavy));
+            },
+            parallel: |v| {
+                black_box(par_map(v, heavy));
+            },
+        },
+        Workload {
+            // 没有元素符合条件，必须检查全部元素
+            name: "any_none",
+            max_exp: u32::MAX,
+            serial: |v| {
+                black_box(v.iter().any(|&n| n < black_box(i32::MIN)));
+            },
+            parallel: |v| {
+                black_box(parallel_any_condition(v, |&n| n < black_box(i32::MIN)));
+            },
+        },
+        Workload {
+            // 符合条件的元素在末尾附近
+            name: "position_late",
+            max_exp: u32::MAX,
+            serial: |v| {
+                let target = v[v.len() * 9 / 10];
+                black_box(v.iter().position(|&n| n == target));
+            },
+            parallel: |v| {
+                let target = v[v.len() * 9 / 10];
+                black_box(par_position(v, |&n| n == target));
+            },
+        },
+        Workload {
+            name: "count_where",
+            max_exp: u32::MAX,
+            serial: |v| {
+                black_box(v.iter().filter(|&&n| n % 3 == 0).count());
+            },
+            parallel: |v| {
+                black_box(par_count_where(v, |&n| n % 3 == 0));
+            },
+        },
+        Workload {
+            name: "partition",
+            max_exp: u32::MAX,
+            serial: |v| {
+                let parts: (Vec<&i32>, Vec<&i32>) = v.iter().partition(|&&n| n % 2 == 0);
+                black_box(parts);
+            },
+            parallel: |v| {
+                black_box(par_partition(v, |&n| n % 2 == 0));
+            },
+        },
+    ]
+}
+
+/// 一个用例的统计结果，时间单位为纳秒（单次运行）。
+#[derive(Debug, Clone, Serialize)]
+struct Stats {
+    samples: usize,
+    iterations_per_sample: u64,
+    median_ns: f64,
+    /// 中位数 95% 置信区间的下界和上界
+    ci95_low_ns: f64,
+    ci95_high_ns: f64,
+    mean_ns: f64,
+    std_dev_ns: f64,
+    min_ns: f64,
+    max_ns: f64,
+}
+
+impl Stats {
+    fn from_samples(mut times: Vec<f64>, iterations_per_sample: u64) -> Self {
+        times.sort_by(f64::total_cmp);
+        let n = times.len();
+        let mean = times.iter().sum::<f64>() / n as f64;
+        let variance = if n > 1 {
+            times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        // 中位数置信区间的次序统计量下标：n/2 ± 1.96·√n/2（二项分布的正态近似）
+        let half_width = 0.98 * (n as f64).sqrt();
+        let low = ((n as f64 / 2.0 - half_width).floor().max(0.0)) as usize;
+        let high = ((n as f64 / 2.0 + half_width).ceil() as usize).min(n - 1);
+
+        Stats {
+            samples: n,
+            iterations_per_sample,
+            median_ns: median(&times),
+            ci95_low_ns: times[low],
+            ci95_high_ns: times[high],
+            mean_ns: mean,
+            std_dev_ns: variance.sqrt(),
+            min_ns: times[0],
+            max_ns: times[n - 1],
+        }
+    }
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let n = sorted.len();
+    if n % 2 == 1 {
+        sorted[n / 2]
+    } else {
+        (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+    }
+}
+
+/// 预热后采集 `samples` 个样本。
+fn measure<F: FnMut()>(samples: usize, mut f: F) -> Stats {
+    let start = Instant::now();
+    let mut warm_up_runs = 0u32;
+    while warm_up_runs < 3 || start.elapsed() < WARM_UP {
+        f();
+        warm_up_runs += 1;
+    }
+    let per_run = start.elapsed() / warm_up_runs;
+    let iterations = (MIN_SAMPLE.as_nanos() / per_run.as_nanos().max(1)).max(1) as u64;
+
+    let times = (0..samples)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                f();
+            }
+            start.elapsed().as_nanos() as f64 / iterations as f64
+        })
+        .collect();
+    Stats::from_samples(times, iterations)
+}
+
+#[derive(Debug, Serialize)]
+struct CaseResult {
+    workload: &'static str,
+    len: usize,
+    variant: &'static str,
+    threads: usize,
+    stats: Stats,
+    /// 串行中位数与并行中位数之比，只有并行用例才有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speedup: Option<f64>,
+    /// 两者的置信区间是否不重叠
+    #[serde(skip_serializing_if = "Option::is_none")]
+    significant: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    timestamp_secs: u64,
+    available_parallelism: usize,
+    samples_per_case: usize,
+    results: Vec<CaseResult>,
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(default)
+}
+
+fn thread_counts(available: usize) -> Vec<usize> {
+    if let Ok(list) = env::var("PARALLEL_BENCH_THREADS") {
+        return list
+            .split(',')
+            .filter_map(|s| s.trim().parse().ok())
+            .filter(|&n| n > 0)
+            .collect();
+    }
+
+    let mut counts: Vec<usize> = std::iter::successors(Some(1), |n| Some(n * 2))
+        .take_while(|&n| n < available)
+        .collect();
+    counts.push(available);
+    counts
+}
+
+fn main() {
+    let max_exp: u32 = env_or("PARALLEL_BENCH_MAX_EXP", 7);
+    let samples: usize = env_or("PARALLEL_BENCH_SAMPLES", 15).max(2);
+    let available = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = thread_counts(available);
+    let report_path = env::var("PARALLEL_BENCH_REPORT").map_or_else(
+        |_| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../target/bench-reports/parallel.json"),
+        PathBuf::from,
+    );
+    // cargo bench 会传入 `--bench` 等参数，其余参数作为负载名过滤条件
+    let filters: Vec<String> = env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with('-'))
+        .collect();
+
+    let pools: Vec<(usize, rayon::ThreadPool)> = threads
+        .iter()
+        .map(|&n| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .expect("failed to build thread pool");
+            (n, pool)
+        })
+        .collect();
+
+    println!(
+        "{:<14} {:>10} {:>8} {:>12} {:>12} {:>9} {:>8} {:>12}",
+        "workload", "len", "threads", "serial", "parallel", "speedup", "±ci", "significant"
+    );
+
+    let mut results = Vec::new();
+    for workload in workloads() {
+        if !filters.is_empty() && !filters.iter().any(|f| workload.name.contains(f.as_str())) {
+            continue;
+        }
+
+        for exp in 3..=max_exp.min(workload.max_exp) {
+            let len = 10usize.pow(exp);
+            let mut input: Vec<i32> = (0..len as i32).collect();
+
+            let serial = measure(samples, || (workload.serial)(&mut input));
+
+            for (n, pool) in &pools {
+                let parallel =
+                    pool.install(|| measure(samples, || (workload.parallel)(&mut input)));
+
+                let speedup = serial.median_ns / parallel.median_ns;
+                let significant = serial.ci95_low_ns > parallel.ci95_high_ns
+                    || parallel.ci95_low_ns > serial.ci95_high_ns;
+                // 并行中位数置信区间的相对半宽
+                let ci = (parallel.ci95_high_ns - parallel.ci95_low_ns) / 2.0 / parallel.median_ns;
+
+                println!(
+                    "{:<14} {:>10} {:>8} {:>12.3?} {:>12.3?} {:>8.2}x {:>7.1}% {:>12}",
+                    workload.name,
+                    len,
+                    n,
+                    Duration::from_nanos(serial.median_ns as u64),
+                    Duration::from_nanos(parallel.median_ns as u64),
+                    speedup,
+                    ci * 100.0,
+                    if significant { "yes" } else { "no" }
+                );
+
+                results.push(CaseResult {
+                    workload: workload.name,
+                    len,
+                    variant: "parallel",
+                    threads: *n,
+                    stats: parallel,
+                    speedup: Some(speedup),
+                    significant: Some(significant),
+                });
+            }
+
+            results.push(CaseResult {
+                workload: workload.name,
+                len,
+                variant: "serial",
+                threads: 1,
+                stats: serial,
+                speedup: None,
+                significant: None,
+            });
+        }
+    }
+
+    let report = Report {
+        timestamp_secs: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        available_parallelism: available,
+        samples_per_case: samples,
+        results,
+    };
+
+    if let Some(dir) = report_path.parent() {
+        fs::create_dir_all(dir).expect("failed to create report directory");
+    }
+    fs::write(&report_path, serde_json::to_string_pretty(&report).unwrap())
+        .expect("failed to write report");
+    println!("report written to {}", report_path.display());
+}
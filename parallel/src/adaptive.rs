@@ -0,0 +1,403 @@
+//! 按测得的代价自动选择串行或并行执行。
+//!
+//! 输入很小时，启动并行任务的开销会超过并行带来的收益，例如 [`parallel_change_arr_ele`]
+//! 处理几千个元素时比 [`serial_change_arr_ele`] 更慢。[`Dispatcher`] 根据以下代价模型做选择：
+//!
+//! * 串行耗时 ≈ `n × c`；
+//! * 并行耗时 ≈ `spawn + 块数 × split / 线程数 + n × c / 线程数`。
+//!
+//! 其中 `spawn`（启动一次并行任务的固定开销）和 `split`（每多一个块的调度开销）
+//! 由 [`Calibration::measure`] 在本机测得，每个元素的代价 `c` 在某个操作第一次执行时
+//! 对开头的一小段元素串行计时得到。校准结果可以保存到文件，下次启动时直接加载。
+//!
+//! [`parallel_change_arr_ele`]: crate::parallel_change_arr::parallel_change_arr_ele
+//! [`serial_change_arr_ele`]: crate::parallel_change_arr::serial_change_arr_ele
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    sync::RwLock,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 首次执行某个操作时，用于测量单个元素代价的前缀长度。
+pub const SAMPLE_LEN: usize = 256;
+
+/// 每个块的工作量至少是调度开销的多少倍。
+const CHUNK_COST_FACTOR: f64 = 10.0;
+
+/// 并行的预计耗时至少要比串行少这个比例才会选择并行，避免在两者相近时来回切换。
+const PARALLEL_MARGIN: f64 = 0.9;
+
+/// 一台机器上的并行开销和各个操作的单元素代价，时间单位为纳秒。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Calibration {
+    /// 校准时线程池的线程数
+    pub threads: usize,
+    /// 启动一次并行任务并等待所有线程完成的固定开销
+    pub spawn_ns: f64,
+    /// 每多划分一个块的调度开销
+    pub split_ns: f64,
+    /// 已测量过的操作的单元素代价，按操作名索引
+    pub costs: BTreeMap<String, f64>,
+}
+
+impl Calibration {
+    /// 在当前的 rayon 线程池中测量并行开销，耗时约几十毫秒。
+    ///
+    /// 需要在之后执行任务的同一个线程池中调用（例如在
+    /// [`ExecutionContext::install`](crate::execution_context::ExecutionContext::install) 内）。
+    pub fn measure() -> Self {
+        const RUNS: usize = 31;
+        const SPLITS: usize = 4096;
+
+        let threads = rayon::current_num_threads();
+        let spawn = median_time(RUNS, || {
+            (0..threads).into_par_iter().with_max_len(1).for_each(|i| {
+                std::hint::black_box(i);
+            })
+        });
+        let split = median_time(RUNS, || {
+            (0..SPLITS).into_par_iter().with_max_len(1).for_each(|i| {
+                std::hint::black_box(i);
+            })
+        });
+
+        Calibration {
+            threads,
+            spawn_ns: spawn,
+            split_ns: ((split - spawn) * threads as f64 / SPLITS as f64).max(0.0),
+            costs: BTreeMap::new(),
+        }
+    }
+
+    /// 从 JSON 文件加载校准结果。
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// 把校准结果保存为 JSON 文件，必要时创建上级目录。
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// 对一次输入选择的执行方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plan {
+    /// 在当前线程中串行执行。
+    Serial,
+    /// 并行执行，每个任务至少处理 `chunk_size` 个元素。
+    Parallel { chunk_size: usize },
+}
+
+/// 根据校准结果在串行和并行之间自动选择的调度器。
+///
+/// 可以在多个线程间共享；操作的单元素代价在第一次执行时测量并缓存。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::adaptive::{Calibration, Dispatcher, Plan};
+///
+/// let dispatcher = Dispatcher::new(Calibration::measure());
+///
+/// let mut arr = vec![1, 2, 3];
+/// dispatcher.map_in_place("increment", &mut arr, |x| *x += 1);
+/// assert_eq!(arr, [2, 3, 4]);
+///
+/// // 三个元素远不足以抵消并行的开销
+/// assert_eq!(dispatcher.plan_for("increment", 3), Some(Plan::Serial));
+/// ```
+#[derive(Debug)]
+pub struct Dispatcher {
+    threads: usize,
+    spawn_ns: f64,
+    split_ns: f64,
+    costs: RwLock<BTreeMap<String, f64>>,
+}
+
+impl Dispatcher {
+    /// 使用给定的校准结果创建调度器。
+    pub fn new(calibration: Calibration) -> Self {
+        Dispatcher {
+            threads: calibration.threads.max(1),
+            spawn_ns: calibration.spawn_ns,
+            split_ns: calibration.split_ns,
+            costs: RwLock::new(calibration.costs),
+        }
+    }
+
+    /// 从 `path` 加载校准结果；文件不存在、无法解析或线程数与当前线程池不同时，
+    /// 重新测量并写回 `path`。
+    ///
+    /// # 返回值
+    ///
+    /// 只有写回文件失败时返回错误。
+    pub fn load_or_calibrate(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        match Calibration::load(path) {
+            Ok(calibration) if calibration.threads == rayon::current_num_threads() => {
+                Ok(Dispatcher::new(calibration))
+            }
+            _ => {
+                let calibration = Calibration::measure();
+                calibration.save(path)?;
+                Ok(Dispatcher::new(calibration))
+            }
+        }
+    }
+
+    /// 当前的校准结果，包括到目前为止测得的所有操作代价，可用于保存。
+    pub fn calibration(&self) -> Calibration {
+        Calibration {
+            threads: self.threads,
+            spawn_ns: self.spawn_ns,
+            split_ns: self.split_ns,
+            costs: self.costs.read().unwrap_or_else(|e| e.into_inner()).clone(),
+        }
+    }
+
+    /// 把当前的校准结果保存到 `path`，参见 [`Calibration::save`]。
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        self.calibration().save(path)
+    }
+
+    /// 已测得的操作单元素代价（纳秒）。
+    pub fn element_cost(&self, op: &str) -> Option<f64> {
+        self.costs
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(op)
+            .copied()
+    }
+
+    /// 按长度和单元素代价（纳秒）选择执行方式。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use parallel::adaptive::{Calibration, Dispatcher, Plan};
+    ///
+    /// let dispatcher = Dispatcher::new(Calibration {
+    ///     threads: 8,
+    ///     spawn_ns: 20_000.0,
+    ///     split_ns: 200.0,
+    ///     costs: BTreeMap::new(),
+    /// });
+    ///
+    /// assert_eq!(dispatcher.plan(1_000, 1.0), Plan::Serial);
+    /// assert_eq!(
+    ///     dispatcher.plan(10_000_000, 1.0),
+    ///     Plan::Parallel { chunk_size: 2_000 }
+    /// );
+    /// ```
+    pub fn plan(&self, len: usize, element_ns: f64) -> Plan {
+        if self.threads < 2 || len < 2 {
+            return Plan::Serial;
+        }
+
+        let element_ns = element_ns.max(f64::MIN_POSITIVE);
+        let threads = self.threads as f64;
+        // 块要足够大以摊薄调度开销，又不能大到让部分线程无事可做
+        let min_chunk = (CHUNK_COST_FACTOR * self.split_ns / element_ns).ceil();
+        let chunk_size = (min_chunk.min(usize::MAX as f64) as usize)
+            .min(len.div_ceil(self.threads))
+            .max(1);
+
+        let n = len as f64;
+        let chunks = len.div_ceil(chunk_size) as f64;
+        let serial = n * element_ns;
+        let parallel = self.spawn_ns + chunks * self.split_ns / threads + serial / threads;
+
+        if parallel < serial * PARALLEL_MARGIN {
+            Plan::Parallel { chunk_size }
+        } else {
+            Plan::Serial
+        }
+    }
+
+    /// 按已测得的代价为操作 `op` 选择执行方式，尚未测量过时返回 `None`。
+    pub fn plan_for(&self, op: &str, len: usize) -> Option<Plan> {
+        self.element_cost(op).map(|cost| self.plan(len, cost))
+    }
+
+    /// 对切片中的每个元素原地执行 `f`，自动选择串行或并行。
+    ///
+    /// 第一次执行 `op` 时，先串行处理开头的至多 [`SAMPLE_LEN`] 个元素并记录单元素代价，
+    /// 再按该代价处理剩余的元素，因此测量不会带来额外的计算。
+    pub fn map_in_place<T, F>(&self, op: &str, slice: &mut [T], f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Sync + Send,
+    {
+        let (cost, rest) = match self.element_cost(op) {
+            Some(cost) => (cost, slice),
+            None => {
+                let (sample, rest) = slice.split_at_mut(slice.len().min(SAMPLE_LEN));
+                let start = Instant::now();
+                sample.iter_mut().for_each(&f);
+                (self.record(op, start.elapsed(), sample.len()), rest)
+            }
+        };
+
+        match self.plan(rest.len(), cost) {
+            Plan::Serial => rest.iter_mut().for_each(f),
+            Plan::Parallel { chunk_size } => {
+                rest.par_iter_mut().with_min_len(chunk_size).for_each(f)
+            }
+        }
+    }
+
+    /// 把 `f` 应用到每个元素并按原有顺序返回结果，自动选择串行或并行，
+    /// 代价的测量方式与 [`map_in_place`](Self::map_in_place) 相同。
+    pub fn map<T, U, F>(&self, op: &str, slice: &[T], f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        let mut result = Vec::with_capacity(slice.len());
+        let (cost, rest) = match self.element_cost(op) {
+            Some(cost) => (cost, slice),
+            None => {
+                let (sample, rest) = slice.split_at(slice.len().min(SAMPLE_LEN));
+                let start = Instant::now();
+                result.extend(sample.iter().map(&f));
+                (self.record(op, start.elapsed(), sample.len()), rest)
+            }
+        };
+
+        match self.plan(rest.len(), cost) {
+            Plan::Serial => result.extend(rest.iter().map(f)),
+            Plan::Parallel { chunk_size } => {
+                result.par_extend(rest.par_iter().with_min_len(chunk_size).map(f))
+            }
+        }
+        result
+    }
+
+    /// 将数组中的每个元素减 1，自动选择 [`serial_change_arr_ele`](crate::serial_change_arr_ele)
+    /// 或 [`parallel_change_arr_ele`](crate::parallel_change_arr_ele) 的执行方式。
+    pub fn change_arr_ele(&self, arr: &mut [i32]) {
+        self.map_in_place("change_arr_ele", arr, |p| *p -= 1)
+    }
+
+    /// 记录 `op` 的单元素代价并返回；其他线程已先记录时沿用已有的值。
+    fn record(&self, op: &str, elapsed: Duration, len: usize) -> f64 {
+        let cost = elapsed.as_nanos() as f64 / len.max(1) as f64;
+        *self
+            .costs
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(op.to_owned())
+            .or_insert(cost)
+    }
+}
+
+/// 运行 `runs` 次 `f`，返回耗时的中位数（纳秒）。
+fn median_time<F: FnMut()>(runs: usize, mut f: F) -> f64 {
+    let mut times: Vec<f64> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed().as_nanos() as f64
+        })
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times[runs / 2]
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration(threads: usize) -> Calibration {
+        Calibration {
+            threads,
+            spawn_ns: 20_000.0,
+            split_ns: 200.0,
+            costs: BTreeMap::new(),
+        }
+    }
+
+    /// 测试小输入和单线程时选择串行，大输入时选择并行且块大小合理
+    #[test]
+    fn plan_follows_cost_model() {
+        let dispatcher = Dispatcher::new(calibration(8));
+        assert_eq!(dispatcher.plan(0, 1.0), Plan::Serial);
+        assert_eq!(dispatcher.plan(10_000, 1.0), Plan::Serial);
+        assert_eq!(
+            dispatcher.plan(10_000_000, 1.0),
+            Plan::Parallel { chunk_size: 2_000 }
+        );
+
+        // 单元素代价很高时，少量元素也值得并行，且每个线程都能分到任务
+        assert_eq!(
+            dispatcher.plan(64, 100_000.0),
+            Plan::Parallel { chunk_size: 1 }
+        );
+        assert_eq!(dispatcher.plan(100, 10.0), Plan::Serial);
+
+        let single = Dispatcher::new(calibration(1));
+        assert_eq!(single.plan(10_000_000, 100_000.0), Plan::Serial);
+    }
+
+    /// 测试首次执行时记录代价，且结果与串行版本一致
+    #[test]
+    fn records_cost_and_matches_serial() {
+        let dispatcher = Dispatcher::new(Calibration::measure());
+        assert_eq!(dispatcher.plan_for("square", 10), None);
+
+        for len in [0, 1, SAMPLE_LEN - 1, SAMPLE_LEN + 1, 100_000] {
+            let input: Vec<u64> = (0..len as u64).collect();
+            let expected: Vec<u64> = input.iter().map(|x| x * x).collect();
+            assert_eq!(dispatcher.map("square", &input, |x| x * x), expected);
+
+            let mut arr = input.clone();
+            dispatcher.map_in_place("square_in_place", &mut arr, |x| *x *= *x);
+            assert_eq!(arr, expected);
+
+            let mut arr: Vec<i32> = (0..len as i32).collect();
+            dispatcher.change_arr_ele(&mut arr);
+            assert!(arr.iter().enumerate().all(|(i, &x)| x == i as i32 - 1));
+        }
+
+        assert!(dispatcher.element_cost("square").is_some());
+        assert!(dispatcher.element_cost("change_arr_ele").is_some());
+    }
+
+    /// 测试校准结果的保存与加载
+    #[test]
+    fn persist_calibration() {
+        let dir = tempfile::tempdir().unwrap();
+        // 保存时会创建缺失的父目录
+        let path = dir.path().join("cache").join("calibration.json");
+
+        let mut saved = calibration(rayon::current_num_threads());
+        saved.costs.insert("op".to_owned(), 3.5);
+        saved.save(&path).unwrap();
+        assert_eq!(Calibration::load(&path).unwrap(), saved);
+
+        // 线程数一致时直接使用文件中的结果
+        let dispatcher = Dispatcher::load_or_calibrate(&path).unwrap();
+        assert_eq!(dispatcher.calibration(), saved);
+        assert_eq!(dispatcher.element_cost("op"), Some(3.5));
+
+        // 文件损坏时重新测量并写回
+        fs::write(&path, "not json").unwrap();
+        let dispatcher = Dispatcher::load_or_calibrate(&path).unwrap();
+        assert_eq!(Calibration::load(&path).unwrap(), dispatcher.calibration());
+    }
+}
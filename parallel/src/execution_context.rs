@@ -0,0 +1,754 @@
+//! 在独立线程池上运行并行任务的执行上下文。
+//!
+//! crate 中的自由函数都运行在 rayon 的全局线程池上。服务程序通常希望把计算密集型任务
+//! 与对延迟敏感的线程隔离开，此时可以用 [`ExecutionContext::builder`] 创建一个专用线程池，
+//! 再通过同名方法调用这些函数，它们内部产生的所有并行任务都只会在该线程池中执行。
+
+use std::{fmt, hash::Hash, ops::Add, path::PathBuf};
+
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use crate::parallel_change_arr::parallel_change_arr_ele;
+use crate::parallel_check_conditions::{
+    par_count_where, par_find_any, par_find_first, par_partition, par_position, par_positions,
+    parallel_all_condition, parallel_any_condition,
+};
+use crate::parallel_files::{par_count_lines, par_grep, par_hash_files, Batch, GrepMatch};
+use crate::parallel_group::{
+    par_aggregate_by_key, par_count_by_key, par_group_by_key, par_max_by_key, par_min_by_key,
+    par_sum_by_key, GroupOrder,
+};
+use crate::parallel_map::{
+    par_map, par_map_chunked, par_map_in_place, par_map_in_place_chunked, par_zip_with,
+    par_zip_with_chunked,
+};
+use crate::parallel_scan::{
+    par_compact, par_dedup, par_exclusive_scan, par_filter, par_inclusive_scan, par_segmented_scan,
+    par_unique,
+};
+use crate::parallel_stats::{
+    par_exact_sum, par_histogram, par_kahan_sum, par_min_max, par_moments, par_pairwise_sum,
+    par_quantiles, Bins, Histogram, Moments, Numeric,
+};
+use crate::parallel_try::{try_par_for_each, try_par_map, FailureMode, TryError};
+use crate::simd::{
+    par_simd_binary, par_simd_binary_scalar, par_simd_change_arr_ele, par_simd_compare, BinaryOp,
+    CmpOp, SimdElement,
+};
+
+/// 创建执行上下文时可能出现的错误。
+#[derive(Debug)]
+pub enum ContextError {
+    /// rayon 无法创建线程池（例如无法创建线程）。
+    ThreadPool(ThreadPoolBuildError),
+    /// 当前平台不支持查询或设置 CPU 亲和性。
+    AffinityUnsupported,
+    /// 指定的 CPU 核心不存在或当前进程不可用。
+    UnknownCore(usize),
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContextError::ThreadPool(e) => write!(f, "failed to build thread pool: {e}"),
+            ContextError::AffinityUnsupported => {
+                write!(f, "CPU affinity is not supported on this platform")
+            }
+            ContextError::UnknownCore(id) => write!(f, "CPU core {id} is not available"),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ContextError::ThreadPool(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ThreadPoolBuildError> for ContextError {
+    fn from(e: ThreadPoolBuildError) -> Self {
+        ContextError::ThreadPool(e)
+    }
+}
+
+/// [`ExecutionContext`] 的构建器。
+///
+/// 未设置的选项使用 rayon 的默认值：线程数为可用的 CPU 数，线程不命名，栈大小由标准库决定。
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionContextBuilder {
+    num_threads: usize,
+    thread_name: Option<String>,
+    stack_size: Option<usize>,
+    cores: Vec<usize>,
+}
+
+impl ExecutionContextBuilder {
+    /// 设置工作线程数，0 表示使用默认值。
+    pub fn num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads;
+        self
+    }
+
+    /// 设置工作线程的名称前缀，第 `i` 个线程命名为 `"{prefix}-{i}"`。
+    pub fn thread_name(mut self, prefix: impl Into<String>) -> Self {
+        self.thread_name = Some(prefix.into());
+        self
+    }
+
+    /// 设置每个工作线程的栈大小（字节）。
+    pub fn stack_size(mut self, bytes: usize) -> Self {
+        self.stack_size = Some(bytes);
+        self
+    }
+
+    /// 把工作线程绑定到指定的 CPU 核心上，第 `i` 个线程绑定到 `cores[i % cores.len()]`。
+    ///
+    /// 未调用 [`num_threads`](Self::num_threads) 时，线程数默认为核心的个数。
+    pub fn pin_to_cores(mut self, cores: impl IntoIterator<Item = usize>) -> Self {
+        self.cores = cores.into_iter().collect();
+        self
+    }
+
+    /// 创建线程池。
+    ///
+    /// # 返回值
+    ///
+    /// 线程池创建失败，或者指定了当前进程不可用的 CPU 核心时返回错误。
+    pub fn build(self) -> Result<ExecutionContext, ContextError> {
+        let mut builder = ThreadPoolBuilder::new();
+
+        let num_threads = if self.num_threads == 0 {
+            self.cores.len()
+        } else {
+            self.num_threads
+        };
+        builder = builder.num_threads(num_threads);
+
+        if let Some(prefix) = self.thread_name {
+            builder = builder.thread_name(move |i| format!("{prefix}-{i}"));
+        }
+        if let Some(bytes) = self.stack_size {
+            builder = builder.stack_size(bytes);
+        }
+
+        if !self.cores.is_empty() {
+            let available =
+                core_affinity::get_core_ids().ok_or(ContextError::AffinityUnsupported)?;
+            let cores = self
+                .cores
+                .iter()
+                .map(|&id| {
+                    available
+                        .iter()
+                        .copied()
+                        .find(|core| core.id == id)
+                        .ok_or(ContextError::UnknownCore(id))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            builder = builder.start_handler(move |i| {
+                // 绑定失败时线程仍可正常工作，只是不受亲和性约束
+                core_affinity::set_for_current(cores[i % cores.len()]);
+            });
+        }
+
+        Ok(ExecutionContext {
+            pool: builder.build()?,
+        })
+    }
+}
+
+/// 包装一个专用 rayon 线程池的执行上下文，crate 中的并行函数都可以作为它的方法调用。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::execution_context::ExecutionContext;
+///
+/// let ctx = ExecutionContext::builder()
+///     .num_threads(2)
+///     .thread_name("compute")
+///     .build()
+///     .unwrap();
+///
+/// let mut arr = vec![1, 2, 3];
+/// ctx.parallel_change_arr_ele(&mut arr);
+/// assert_eq!(arr, [0, 1, 2]);
+///
+/// assert_eq!(ctx.par_map(&arr, |x| x * 10), [0, 10, 20]);
+/// assert!(ctx.install(|| rayon::current_thread_index().is_some()));
+/// ```
+#[derive(Debug)]
+pub struct ExecutionContext {
+    pool: ThreadPool,
+}
+
+impl ExecutionContext {
+    /// 返回一个使用默认选项的构建器。
+    pub fn builder() -> ExecutionContextBuilder {
+        ExecutionContextBuilder::default()
+    }
+
+    /// 线程池中的工作线程数。
+    pub fn num_threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+
+    /// 在线程池中执行 `op` 并等待其返回，`op` 内部的所有 rayon 并行操作都使用该线程池。
+    pub fn install<R, OP>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        self.pool.install(op)
+    }
+
+    /// 在线程池中创建一个作用域，作用域内派生的任务可以借用外部数据，返回前等待它们全部完成。
+    pub fn scope<'scope, R, OP>(&self, op: OP) -> R
+    where
+        OP: FnOnce(&rayon::Scope<'scope>) -> R + Send,
+        R: Send,
+    {
+        self.pool.scope(op)
+    }
+
+    /// 在线程池中异步执行 `op`，不等待其完成。
+    pub fn spawn<OP>(&self, op: OP)
+    where
+        OP: FnOnce() + Send + 'static,
+    {
+        self.pool.spawn(op)
+    }
+
+    /// 参见 [`parallel_change_arr_ele`]。
+    pub fn parallel_change_arr_ele(&self, arr: &mut [i32]) {
+        self.install(|| parallel_change_arr_ele(arr))
+    }
+
+    /// 参见 [`parallel_any_condition`]。
+    pub fn parallel_any_condition<T, F>(&self, vec: &[T], condition: F) -> bool
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| parallel_any_condition(vec, &condition))
+    }
+
+    /// 参见 [`parallel_all_condition`]。
+    pub fn parallel_all_condition<T, F>(&self, vec: &[T], condition: F) -> bool
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| parallel_all_condition(vec, &condition))
+    }
+
+    /// 参见 [`par_find_first`]。
+    pub fn par_find_first<'a, T, F>(&self, vec: &'a [T], condition: F) -> Option<&'a T>
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_find_first(vec, &condition))
+    }
+
+    /// 参见 [`par_find_any`]。
+    pub fn par_find_any<'a, T, F>(&self, vec: &'a [T], condition: F) -> Option<&'a T>
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_find_any(vec, &condition))
+    }
+
+    /// 参见 [`par_position`]。
+    pub fn par_position<T, F>(&self, vec: &[T], condition: F) -> Option<usize>
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_position(vec, &condition))
+    }
+
+    /// 参见 [`par_positions`]。
+    pub fn par_positions<T, F>(&self, vec: &[T], condition: F) -> Vec<usize>
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_positions(vec, &condition))
+    }
+
+    /// 参见 [`par_count_where`]。
+    pub fn par_count_where<T, F>(&self, vec: &[T], condition: F) -> usize
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_count_where(vec, &condition))
+    }
+
+    /// 参见 [`par_partition`]。
+    pub fn par_partition<'a, T, F>(&self, vec: &'a [T], condition: F) -> (Vec<&'a T>, Vec<&'a T>)
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_partition(vec, &condition))
+    }
+
+    /// 参见 [`par_map_in_place`]。
+    pub fn par_map_in_place<T, F>(&self, slice: &mut [T], f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Sync + Send,
+    {
+        self.install(|| par_map_in_place(slice, f))
+    }
+
+    /// 参见 [`par_map_in_place_chunked`]。
+    pub fn par_map_in_place_chunked<T, F>(&self, slice: &mut [T], chunk_size: usize, f: F)
+    where
+        T: Send,
+        F: Fn(&mut T) + Sync + Send,
+    {
+        self.install(|| par_map_in_place_chunked(slice, chunk_size, f))
+    }
+
+    /// 参见 [`par_map`]。
+    pub fn par_map<T, U, F>(&self, slice: &[T], f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        self.install(|| par_map(slice, f))
+    }
+
+    /// 参见 [`par_map_chunked`]。
+    pub fn par_map_chunked<T, U, F>(&self, slice: &[T], chunk_size: usize, f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        self.install(|| par_map_chunked(slice, chunk_size, f))
+    }
+
+    /// 参见 [`par_zip_with`]。
+    pub fn par_zip_with<A, B, U, F>(&self, a: &[A], b: &[B], f: F) -> Vec<U>
+    where
+        A: Sync,
+        B: Sync,
+        U: Send,
+        F: Fn(&A, &B) -> U + Sync + Send,
+    {
+        self.install(|| par_zip_with(a, b, f))
+    }
+
+    /// 参见 [`par_zip_with_chunked`]。
+    pub fn par_zip_with_chunked<A, B, U, F>(
+        &self,
+        a: &[A],
+        b: &[B],
+        chunk_size: usize,
+        f: F,
+    ) -> Vec<U>
+    where
+        A: Sync,
+        B: Sync,
+        U: Send,
+        F: Fn(&A, &B) -> U + Sync + Send,
+    {
+        self.install(|| par_zip_with_chunked(a, b, chunk_size, f))
+    }
+
+    /// 参见 [`par_kahan_sum`]。
+    pub fn par_kahan_sum<T: Numeric>(&self, slice: &[T]) -> f64 {
+        self.install(|| par_kahan_sum(slice))
+    }
+
+    /// 参见 [`par_pairwise_sum`]。
+    pub fn par_pairwise_sum<T: Numeric>(&self, slice: &[T]) -> f64 {
+        self.install(|| par_pairwise_sum(slice))
+    }
+
+    /// 参见 [`par_exact_sum`]。
+    pub fn par_exact_sum<T: Copy + Into<i128> + Sync>(&self, slice: &[T]) -> i128 {
+        self.install(|| par_exact_sum(slice))
+    }
+
+    /// 参见 [`par_min_max`]。
+    pub fn par_min_max<T: Numeric>(&self, slice: &[T]) -> Option<(T, T)> {
+        self.install(|| par_min_max(slice))
+    }
+
+    /// 参见 [`par_moments`]。
+    pub fn par_moments<T: Numeric>(&self, slice: &[T]) -> Moments {
+        self.install(|| par_moments(slice))
+    }
+
+    /// 参见 [`par_histogram`]。
+    pub fn par_histogram<T: Numeric>(&self, slice: &[T], bins: &Bins) -> Histogram {
+        self.install(|| par_histogram(slice, bins))
+    }
+
+    /// 参见 [`par_quantiles`]。
+    pub fn par_quantiles<T: Numeric>(&self, slice: &[T], qs: &[f64]) -> Option<Vec<f64>> {
+        self.install(|| par_quantiles(slice, qs))
+    }
+
+    /// 参见 [`par_inclusive_scan`]。
+    pub fn par_inclusive_scan<T, F>(&self, slice: &[T], op: F) -> Vec<T>
+    where
+        T: Clone + Send + Sync,
+        F: Fn(&T, &T) -> T + Sync,
+    {
+        self.install(|| par_inclusive_scan(slice, &op))
+    }
+
+    /// 参见 [`par_exclusive_scan`]。
+    pub fn par_exclusive_scan<T, F>(&self, slice: &[T], identity: T, op: F) -> Vec<T>
+    where
+        T: Clone + Send + Sync,
+        F: Fn(&T, &T) -> T + Sync,
+    {
+        self.install(|| par_exclusive_scan(slice, identity, &op))
+    }
+
+    /// 参见 [`par_segmented_scan`]。
+    pub fn par_segmented_scan<T, F>(&self, slice: &[T], heads: &[bool], op: F) -> Vec<T>
+    where
+        T: Clone + Send + Sync,
+        F: Fn(&T, &T) -> T + Sync,
+    {
+        self.install(|| par_segmented_scan(slice, heads, &op))
+    }
+
+    /// 参见 [`par_filter`]。
+    pub fn par_filter<T, F>(&self, slice: &[T], predicate: F) -> Vec<T>
+    where
+        T: Clone + Send + Sync,
+        F: Fn(&T) -> bool + Sync,
+    {
+        self.install(|| par_filter(slice, &predicate))
+    }
+
+    /// 参见 [`par_compact`]。
+    pub fn par_compact<T: Clone + Send + Sync>(&self, slice: &[T], keep: &[bool]) -> Vec<T> {
+        self.install(|| par_compact(slice, keep))
+    }
+
+    /// 参见 [`par_dedup`]。
+    pub fn par_dedup<T: PartialEq + Clone + Send + Sync>(&self, slice: &[T]) -> Vec<T> {
+        self.install(|| par_dedup(slice))
+    }
+
+    /// 参见 [`par_unique`]。
+    pub fn par_unique<T: Ord + Clone + Send + Sync>(&self, slice: &[T]) -> Vec<T> {
+        self.install(|| par_unique(slice))
+    }
+
+    /// 参见 [`par_hash_files`]。
+    pub fn par_hash_files(&self, root: impl Into<PathBuf>) -> Batch<String> {
+        let root = root.into();
+        self.install(|| par_hash_files(root))
+    }
+
+    /// 参见 [`par_count_lines`]。
+    pub fn par_count_lines(&self, root: impl Into<PathBuf>) -> Batch<usize> {
+        let root = root.into();
+        self.install(|| par_count_lines(root))
+    }
+
+    /// 参见 [`par_grep`]。
+    pub fn par_grep(&self, root: impl Into<PathBuf>, pattern: &str) -> Batch<Vec<GrepMatch>> {
+        let root = root.into();
+        self.install(|| par_grep(root, pattern))
+    }
+
+    /// 参见 [`par_simd_binary`]。
+    pub fn par_simd_binary<T: SimdElement>(&self, op: BinaryOp, a: &[T], b: &[T], out: &mut [T]) {
+        self.install(|| par_simd_binary(op, a, b, out))
+    }
+
+    /// 参见 [`par_simd_binary_scalar`]。
+    pub fn par_simd_binary_scalar<T: SimdElement>(&self, op: BinaryOp, slice: &mut [T], scalar: T) {
+        self.install(|| par_simd_binary_scalar(op, slice, scalar))
+    }
+
+    /// 参见 [`par_simd_compare`]。
+    pub fn par_simd_compare<T: SimdElement>(&self, op: CmpOp, a: &[T], b: &[T], out: &mut [bool]) {
+        self.install(|| par_simd_compare(op, a, b, out))
+    }
+
+    /// 参见 [`par_simd_change_arr_ele`]。
+    pub fn par_simd_change_arr_ele(&self, arr: &mut [i32]) {
+        self.install(|| par_simd_change_arr_ele(arr))
+    }
+
+    /// 参见 [`par_aggregate_by_key`]。
+    pub fn par_aggregate_by_key<'a, T, K, A, KF, I, F, M>(
+        &self,
+        slice: &'a [T],
+        key: KF,
+        init: I,
+        fold: F,
+        merge: M,
+        order: GroupOrder,
+    ) -> Vec<(K, A)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        A: Send,
+        KF: Fn(&T) -> K + Sync + Send,
+        I: Fn() -> A + Sync + Send,
+        F: Fn(&mut A, &'a T) + Sync + Send,
+        M: Fn(&mut A, A) + Sync + Send,
+    {
+        self.install(|| par_aggregate_by_key(slice, key, init, fold, merge, order))
+    }
+
+    /// 参见 [`par_group_by_key`]。
+    pub fn par_group_by_key<'a, T, K, KF>(
+        &self,
+        slice: &'a [T],
+        key: KF,
+        order: GroupOrder,
+    ) -> Vec<(K, Vec<&'a T>)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        KF: Fn(&T) -> K + Sync + Send,
+    {
+        self.install(|| par_group_by_key(slice, key, order))
+    }
+
+    /// 参见 [`par_count_by_key`]。
+    pub fn par_count_by_key<T, K, KF>(
+        &self,
+        slice: &[T],
+        key: KF,
+        order: GroupOrder,
+    ) -> Vec<(K, usize)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        KF: Fn(&T) -> K + Sync + Send,
+    {
+        self.install(|| par_count_by_key(slice, key, order))
+    }
+
+    /// 参见 [`par_sum_by_key`]。
+    pub fn par_sum_by_key<T, K, V, KF, VF>(
+        &self,
+        slice: &[T],
+        key: KF,
+        value: VF,
+        order: GroupOrder,
+    ) -> Vec<(K, V)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        V: Copy + Default + Add<Output = V> + Send,
+        KF: Fn(&T) -> K + Sync + Send,
+        VF: Fn(&T) -> V + Sync + Send,
+    {
+        self.install(|| par_sum_by_key(slice, key, value, order))
+    }
+
+    /// 参见 [`par_min_by_key`]。
+    pub fn par_min_by_key<T, K, V, KF, VF>(
+        &self,
+        slice: &[T],
+        key: KF,
+        value: VF,
+        order: GroupOrder,
+    ) -> Vec<(K, V)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        V: PartialOrd + Copy + Send,
+        KF: Fn(&T) -> K + Sync + Send,
+        VF: Fn(&T) -> V + Sync + Send,
+    {
+        self.install(|| par_min_by_key(slice, key, value, order))
+    }
+
+    /// 参见 [`par_max_by_key`]。
+    pub fn par_max_by_key<T, K, V, KF, VF>(
+        &self,
+        slice: &[T],
+        key: KF,
+        value: VF,
+        order: GroupOrder,
+    ) -> Vec<(K, V)>
+    where
+        T: Sync,
+        K: Eq + Hash + Send,
+        V: PartialOrd + Copy + Send,
+        KF: Fn(&T) -> K + Sync + Send,
+        VF: Fn(&T) -> V + Sync + Send,
+    {
+        self.install(|| par_max_by_key(slice, key, value, order))
+    }
+
+    /// 参见 [`try_par_for_each`]。
+    pub fn try_par_for_each<T, E, F>(
+        &self,
+        slice: &[T],
+        mode: FailureMode,
+        f: F,
+    ) -> Result<(), Vec<TryError<E>>>
+    where
+        T: Sync,
+        E: Send,
+        F: Fn(&T) -> Result<(), E> + Sync + Send,
+    {
+        self.install(|| try_par_for_each(slice, mode, f))
+    }
+
+    /// 参见 [`try_par_map`]。
+    pub fn try_par_map<T, U, E, F>(
+        &self,
+        slice: &[T],
+        mode: FailureMode,
+        f: F,
+    ) -> Result<Vec<U>, Vec<TryError<E>>>
+    where
+        T: Sync,
+        U: Send,
+        E: Send,
+        F: Fn(&T) -> Result<U, E> + Sync + Send,
+    {
+        self.install(|| try_par_map(slice, mode, f))
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    /// 测试线程数和线程名按配置生效，且任务只在该线程池中执行
+    #[test]
+    fn runs_on_configured_pool() {
+        let ctx = ExecutionContext::builder()
+            .num_threads(3)
+            .thread_name("worker")
+            .stack_size(4 * 1024 * 1024)
+            .build()
+            .unwrap();
+        assert_eq!(ctx.num_threads(), 3);
+
+        let names = Mutex::new(HashSet::new());
+        let input: Vec<u32> = (0..10_000).collect();
+        let doubled = ctx.par_map_chunked(&input, 16, |&x| {
+            let name = std::thread::current().name().map(str::to_owned);
+            names.lock().unwrap().insert(name);
+            x * 2
+        });
+
+        assert_eq!(doubled, input.iter().map(|x| x * 2).collect::<Vec<_>>());
+        for name in names.into_inner().unwrap() {
+            let name = name.expect("worker threads are named");
+            assert!(["worker-0", "worker-1", "worker-2"].contains(&name.as_str()));
+        }
+    }
+
+    /// 测试方法与对应的自由函数结果一致
+    #[test]
+    fn methods_match_free_functions() {
+        let ctx = ExecutionContext::builder().num_threads(2).build().unwrap();
+        let vec: Vec<i32> = (0..1_000).collect();
+        let even = |n: &i32| n % 2 == 0;
+
+        assert!(ctx.parallel_any_condition(&vec, |&n| n == 999));
+        assert!(!ctx.parallel_all_condition(&vec, even));
+        assert_eq!(ctx.par_find_first(&vec, |&n| n > 10), Some(&11));
+        assert_eq!(ctx.par_find_any(&vec, |&n| n == 42), Some(&42));
+        assert_eq!(ctx.par_position(&vec, |&n| n == 500), Some(500));
+        assert_eq!(ctx.par_positions(&vec, even), par_positions(&vec, even));
+        assert_eq!(ctx.par_count_where(&vec, even), 500);
+        assert_eq!(ctx.par_partition(&vec, even), par_partition(&vec, even));
+        assert_eq!(
+            ctx.par_zip_with_chunked(&vec, &vec, 64, |a, b| a + b),
+            par_zip_with(&vec, &vec, |a, b| a + b)
+        );
+
+        assert_eq!(ctx.par_exact_sum(&vec), 499_500);
+        assert_eq!(ctx.par_pairwise_sum(&vec), par_pairwise_sum(&vec));
+        assert_eq!(ctx.par_min_max(&vec), Some((0, 999)));
+        assert_eq!(ctx.par_moments(&vec).mean(), Some(499.5));
+        assert_eq!(
+            ctx.try_par_map(&vec, FailureMode::CollectAll, |&n| n
+                .checked_sub(1)
+                .ok_or(n)),
+            Ok(vec.iter().map(|n| n - 1).collect())
+        );
+        assert_eq!(
+            ctx.par_count_by_key(&vec, |n| n % 4, GroupOrder::FirstSeen),
+            par_count_by_key(&vec, |n| n % 4, GroupOrder::FirstSeen)
+        );
+        assert_eq!(
+            ctx.par_max_by_key(&vec, |n| n % 2, |&n| n, GroupOrder::FirstSeen),
+            [(0, 998), (1, 999)]
+        );
+        assert!(ctx
+            .try_par_for_each(&vec, FailureMode::FailFast, |&n| if n < 999 {
+                Ok(())
+            } else {
+                Err(n)
+            })
+            .is_err());
+
+        let mut arr = vec.clone();
+        ctx.par_map_in_place_chunked(&mut arr, 8, |x| *x *= 2);
+        ctx.parallel_change_arr_ele(&mut arr);
+        assert_eq!(arr, vec.iter().map(|x| x * 2 - 1).collect::<Vec<_>>());
+    }
+
+    /// 测试作用域任务可以借用外部数据并在线程池中执行
+    #[test]
+    fn scope_borrows_local_data() {
+        let ctx = ExecutionContext::builder().num_threads(2).build().unwrap();
+        let mut left = vec![0; 100];
+        let mut right = vec![0; 100];
+
+        ctx.scope(|s| {
+            s.spawn(|_| left.iter_mut().for_each(|x| *x += 1));
+            s.spawn(|_| right.iter_mut().for_each(|x| *x += 2));
+        });
+
+        assert!(left.iter().all(|&x| x == 1));
+        assert!(right.iter().all(|&x| x == 2));
+    }
+
+    /// 测试绑定到可用核心时成功创建，绑定到不存在的核心时返回错误
+    #[test]
+    fn pin_to_cores() {
+        let Some(available) = core_affinity::get_core_ids() else {
+            return;
+        };
+        let first = available[0].id;
+
+        let ctx = ExecutionContext::builder()
+            .pin_to_cores([first])
+            .build()
+            .unwrap();
+        assert_eq!(ctx.num_threads(), 1);
+        assert_eq!(ctx.par_count_where(&[1, 2, 3], |&n| n > 1), 2);
+
+        let missing = available.iter().map(|c| c.id).max().unwrap() + 1;
+        let err = ExecutionContext::builder()
+            .pin_to_cores([first, missing])
+            .build()
+            .unwrap_err();
+        assert!(matches!(err, ContextError::UnknownCore(id) if id == missing));
+    }
+}
@@ -1,5 +1,24 @@
+pub mod adaptive;
+pub mod execution_context;
+pub mod matrix;
 pub mod parallel_change_arr;
 pub mod parallel_check_conditions;
+pub mod parallel_files;
+pub mod parallel_group;
+pub mod parallel_map;
+pub mod parallel_scan;
+pub mod parallel_stats;
+pub mod parallel_try;
+pub mod simd;
 
+pub use execution_context::*;
+pub use matrix::*;
 pub use parallel_change_arr::*;
 pub use parallel_check_conditions::*;
+pub use parallel_files::*;
+pub use parallel_group::*;
+pub use parallel_map::*;
+pub use parallel_scan::*;
+pub use parallel_stats::*;
+pub use parallel_try::*;
+pub use simd::*;
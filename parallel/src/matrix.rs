@@ -0,0 +1,723 @@
+//! 行优先存储的矩阵与二维网格上的并行运算。
+//!
+//! 所有并行方法都以行带（若干连续的行）为单位划分任务，每个任务只写自己的输出行，
+//! 因此不需要加锁；方法运行在当前的 rayon 线程池中，需要隔离时可以在
+//! [`ExecutionContext::install`](crate::execution_context::ExecutionContext::install) 内调用。
+//! 每个并行方法都有 `serial_*` 参考实现，对于浮点数两者的结果也逐位相同。
+
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use rayon::prelude::*;
+
+/// 分块运算的块大小，每个维度至少为 1。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    rows: usize,
+    cols: usize,
+}
+
+impl Tile {
+    /// 创建块大小，为 0 的维度按 1 处理。
+    pub const fn new(rows: usize, cols: usize) -> Self {
+        Tile {
+            rows: if rows == 0 { 1 } else { rows },
+            cols: if cols == 0 { 1 } else { cols },
+        }
+    }
+
+    /// 每块的行数，也是并行任务的行带高度。
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// 每块的列数。
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+}
+
+impl Default for Tile {
+    /// 64 × 64 的块，对于 8 字节的元素约为 32 KiB，通常能放进 L1 缓存。
+    fn default() -> Self {
+        Tile::new(64, 64)
+    }
+}
+
+/// 模板运算访问网格外的位置时的取值方式。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary<T> {
+    /// 取最近的边界元素
+    Clamp,
+    /// 从另一侧绕回，即把网格视为环面
+    Wrap,
+    /// 取固定的值
+    Constant(T),
+}
+
+/// 行优先存储的稠密矩阵。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::matrix::Matrix;
+///
+/// let mut m = Matrix::from_fn(2, 3, |r, c| (r * 3 + c) as i32);
+/// m.par_map_in_place(|x| *x -= 1);
+///
+/// assert_eq!(m.as_slice(), [-1, 0, 1, 2, 3, 4]);
+/// assert_eq!(m[(1, 2)], 4);
+/// assert_eq!(m.par_transpose().row(2), [1, 4]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// 用行优先排列的数据创建矩阵。
+    ///
+    /// # Panics
+    ///
+    /// `data.len()` 不等于 `rows * cols` 时 panic。
+    pub fn from_vec(rows: usize, cols: usize, data: Vec<T>) -> Self {
+        assert_eq!(
+            data.len(),
+            rows * cols,
+            "matrix: data length does not match {rows}x{cols}"
+        );
+        Matrix { rows, cols, data }
+    }
+
+    /// 用 `f(行, 列)` 生成每个元素。
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(rows: usize, cols: usize, mut f: F) -> Self {
+        let data = (0..rows * cols).map(|i| f(i / cols, i % cols)).collect();
+        Matrix { rows, cols, data }
+    }
+
+    /// 行数。
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// 列数。
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// 行优先排列的全部元素。
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// 行优先排列的全部元素的可变引用。
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// 取出底层数据。
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// 第 `r` 行。
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// 第 `r` 行的可变引用。
+    pub fn row_mut(&mut self, r: usize) -> &mut [T] {
+        &mut self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    /// 位于 `(r, c)` 的元素，越界时返回 `None`。
+    pub fn get(&self, r: usize, c: usize) -> Option<&T> {
+        (r < self.rows && c < self.cols).then(|| &self.data[r * self.cols + c])
+    }
+
+    fn assert_same_shape<U>(&self, other: &Matrix<U>) {
+        assert!(
+            self.rows == other.rows && self.cols == other.cols,
+            "matrix: shapes {}x{} and {}x{} differ",
+            self.rows,
+            self.cols,
+            other.rows,
+            other.cols
+        );
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// 所有元素都为 `value` 的矩阵。
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Matrix::from_vec(rows, cols, vec![value; rows * cols])
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "matrix: index out of bounds"
+        );
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        assert!(
+            r < self.rows && c < self.cols,
+            "matrix: index out of bounds"
+        );
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+impl<T: Send + Sync> Matrix<T> {
+    /// 并行地对每个元素原地执行 `f`。
+    pub fn par_map_in_place<F>(&mut self, f: F)
+    where
+        F: Fn(&mut T) + Sync + Send,
+    {
+        self.data.par_iter_mut().for_each(f)
+    }
+
+    /// 并行地把 `f` 应用到每个元素，返回同样形状的新矩阵。
+    pub fn par_map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self.data.par_iter().map(f).collect(),
+        }
+    }
+
+    /// 并行地把 `f` 逐对应用到两个同样形状矩阵的元素上。
+    ///
+    /// # Panics
+    ///
+    /// 两个矩阵形状不同时 panic。
+    pub fn par_zip_with<U, V, F>(&self, other: &Matrix<U>, f: F) -> Matrix<V>
+    where
+        U: Sync,
+        V: Send,
+        F: Fn(&T, &U) -> V + Sync + Send,
+    {
+        self.assert_same_shape(other);
+        Matrix {
+            rows: self.rows,
+            cols: self.cols,
+            data: self
+                .data
+                .par_iter()
+                .zip(&other.data)
+                .map(|(a, b)| f(a, b))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + Send + Sync + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    /// 并行的逐元素加法。
+    pub fn par_add(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.par_zip_with(other, |&a, &b| a + b)
+    }
+
+    /// 并行的逐元素减法。
+    pub fn par_sub(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.par_zip_with(other, |&a, &b| a - b)
+    }
+
+    /// 并行的逐元素乘法（Hadamard 积）。
+    pub fn par_hadamard(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.par_zip_with(other, |&a, &b| a * b)
+    }
+}
+
+impl<T: Copy + Send + Sync> Matrix<T> {
+    /// 串行转置，作为 [`par_transpose`](Self::par_transpose) 的对照。
+    pub fn serial_transpose(&self) -> Matrix<T> {
+        let data = (0..self.rows * self.cols)
+            .map(|i| self.data[(i % self.rows) * self.cols + i / self.rows])
+            .collect();
+        Matrix::from_vec(self.cols, self.rows, data)
+    }
+
+    /// 使用默认块大小的并行转置，参见 [`par_transpose_tiled`](Self::par_transpose_tiled)。
+    pub fn par_transpose(&self) -> Matrix<T> {
+        self.par_transpose_tiled(Tile::default())
+    }
+
+    /// 分块并行转置。
+    ///
+    /// 逐行写输出时按列读输入，缓存命中率很低；分块后每次只在一个 `tile` 大小的区域内读写，
+    /// 输入和输出都能留在缓存中。输出按 `tile.rows()` 行一组并行处理。
+    pub fn par_transpose_tiled(&self, tile: Tile) -> Matrix<T> {
+        let (rows, cols) = (self.rows, self.cols);
+        let mut out = Vec::with_capacity(rows * cols);
+        if rows * cols == 0 {
+            return Matrix::from_vec(cols, rows, out);
+        }
+
+        // 输出的第 i 行是输入的第 i 列；先用输入的第一个元素占位，随后全部覆盖
+        out.resize(rows * cols, self.data[0]);
+        out.par_chunks_mut(tile.rows() * rows)
+            .enumerate()
+            .for_each(|(band, out_rows)| {
+                let first = band * tile.rows();
+                let height = out_rows.len() / rows;
+                for c0 in (0..rows).step_by(tile.cols()) {
+                    let c1 = (c0 + tile.cols()).min(rows);
+                    for i in 0..height {
+                        for j in c0..c1 {
+                            out_rows[i * rows + j] = self.data[j * cols + first + i];
+                        }
+                    }
+                }
+            });
+        Matrix::from_vec(cols, rows, out)
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + Default + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    /// 串行的矩阵乘法（i-k-j 循环顺序），作为 [`par_matmul`](Self::par_matmul) 的对照。
+    ///
+    /// # Panics
+    ///
+    /// `self` 的列数不等于 `other` 的行数时 panic。
+    pub fn serial_matmul(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.assert_can_multiply(other);
+        let mut out = Matrix::filled(self.rows, other.cols, T::default());
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self.data[i * self.cols + k];
+                for (o, &b) in out.row_mut(i).iter_mut().zip(other.row(k)) {
+                    *o = *o + a * b;
+                }
+            }
+        }
+        out
+    }
+
+    /// 使用默认块大小的并行矩阵乘法，参见 [`par_matmul_tiled`](Self::par_matmul_tiled)。
+    pub fn par_matmul(&self, other: &Matrix<T>) -> Matrix<T> {
+        self.par_matmul_tiled(other, Tile::default())
+    }
+
+    /// 分块并行矩阵乘法。
+    ///
+    /// 输出按 `tile.rows()` 行一组并行计算；每组内部再按 `tile.cols()` 把 k 和 j 两个维度分块，
+    /// 使参与计算的 `other` 的子块留在缓存中。k 的块按升序处理，每个输出元素的累加顺序与
+    /// [`serial_matmul`](Self::serial_matmul) 完全相同，因此浮点结果也逐位一致。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use parallel::matrix::{Matrix, Tile};
+    ///
+    /// let a = Matrix::from_vec(2, 3, vec![1, 2, 3, 4, 5, 6]);
+    /// let b = Matrix::from_vec(3, 2, vec![7, 8, 9, 10, 11, 12]);
+    ///
+    /// let c = a.par_matmul_tiled(&b, Tile::new(1, 2));
+    /// assert_eq!(c.as_slice(), [58, 64, 139, 154]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// `self` 的列数不等于 `other` 的行数时 panic。
+    pub fn par_matmul_tiled(&self, other: &Matrix<T>, tile: Tile) -> Matrix<T> {
+        self.assert_can_multiply(other);
+        let (n, m) = (self.cols, other.cols);
+        let mut out = vec![T::default(); self.rows * m];
+        if m == 0 {
+            return Matrix::from_vec(self.rows, m, out);
+        }
+
+        out.par_chunks_mut(tile.rows() * m)
+            .enumerate()
+            .for_each(|(band, out_rows)| {
+                let first = band * tile.rows();
+                let height = out_rows.len() / m;
+                for k0 in (0..n).step_by(tile.cols()) {
+                    let k1 = (k0 + tile.cols()).min(n);
+                    for j0 in (0..m).step_by(tile.cols()) {
+                        let j1 = (j0 + tile.cols()).min(m);
+                        for i in 0..height {
+                            let a_row = self.row(first + i);
+                            let out_row = &mut out_rows[i * m + j0..i * m + j1];
+                            for (k, &a) in a_row.iter().enumerate().take(k1).skip(k0) {
+                                let b_row = &other.row(k)[j0..j1];
+                                for (o, &b) in out_row.iter_mut().zip(b_row) {
+                                    *o = *o + a * b;
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        Matrix::from_vec(self.rows, m, out)
+    }
+
+    fn assert_can_multiply(&self, other: &Matrix<T>) {
+        assert_eq!(
+            self.cols, other.rows,
+            "matrix: cannot multiply {}x{} by {}x{}",
+            self.rows, self.cols, other.rows, other.cols
+        );
+    }
+}
+
+/// 模板运算中以某个元素为中心的邻域。
+#[derive(Debug)]
+pub struct Window<'a, T> {
+    grid: &'a Matrix<T>,
+    row: usize,
+    col: usize,
+    boundary: Boundary<T>,
+}
+
+impl<T: Copy> Window<'_, T> {
+    /// 中心元素所在的行和列。
+    pub fn position(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// 中心元素。
+    pub fn center(&self) -> T {
+        self.grid.data[self.row * self.grid.cols + self.col]
+    }
+
+    /// 相对中心偏移 `(dr, dc)` 的元素，越界时按边界条件取值。
+    pub fn get(&self, dr: isize, dc: isize) -> T {
+        let resolve = |index: usize, delta: isize, len: usize| -> Option<usize> {
+            let target = index as isize + delta;
+            if (0..len as isize).contains(&target) {
+                return Some(target as usize);
+            }
+            match self.boundary {
+                Boundary::Clamp => Some(target.clamp(0, len as isize - 1) as usize),
+                Boundary::Wrap => Some(target.rem_euclid(len as isize) as usize),
+                Boundary::Constant(_) => None,
+            }
+        };
+        let r = resolve(self.row, dr, self.grid.rows);
+        let c = resolve(self.col, dc, self.grid.cols);
+        match (r, c, self.boundary) {
+            (Some(r), Some(c), _) => self.grid.data[r * self.grid.cols + c],
+            (_, _, Boundary::Constant(value)) => value,
+            _ => unreachable!("only constant boundaries leave the grid"),
+        }
+    }
+}
+
+impl<T: Copy + Send + Sync> Matrix<T> {
+    /// 串行的模板运算，作为 [`par_stencil`](Self::par_stencil) 的对照。
+    pub fn serial_stencil<U, F>(&self, boundary: Boundary<T>, f: F) -> Matrix<U>
+    where
+        F: Fn(&Window<'_, T>) -> U,
+    {
+        Matrix::from_fn(self.rows, self.cols, |row, col| {
+            f(&Window {
+                grid: self,
+                row,
+                col,
+                boundary,
+            })
+        })
+    }
+
+    /// 分块并行的模板运算：输出的每个元素为 `f(以该元素为中心的邻域)`。
+    ///
+    /// 输出按 `tile.rows()` 行一组并行计算，组内按 `tile.cols()` 列分块遍历，
+    /// 使相邻几行的输入在处理一个块时都留在缓存中。
+    ///
+    /// # 示例
+    ///
+    /// 生命游戏在环面上的一步：
+    ///
+    /// ```
+    /// use parallel::matrix::{Boundary, Matrix, Tile};
+    ///
+    /// // 横向的三个细胞（闪烁振荡器）
+    /// let mut grid = Matrix::filled(5, 5, false);
+    /// for c in 1..4 {
+    ///     grid[(2, c)] = true;
+    /// }
+    ///
+    /// let next = grid.par_stencil(Boundary::Wrap, Tile::new(2, 2), |w| {
+    ///     let neighbours = (-1..=1)
+    ///         .flat_map(|dr| (-1..=1).map(move |dc| (dr, dc)))
+    ///         .filter(|&(dr, dc)| (dr, dc) != (0, 0) && w.get(dr, dc))
+    ///         .count();
+    ///     matches!((w.center(), neighbours), (true, 2) | (_, 3))
+    /// });
+    ///
+    /// // 变为纵向
+    /// assert!((1..4).all(|r| next[(r, 2)]));
+    /// assert_eq!(next.as_slice().iter().filter(|&&alive| alive).count(), 3);
+    /// ```
+    pub fn par_stencil<U, F>(&self, boundary: Boundary<T>, tile: Tile, f: F) -> Matrix<U>
+    where
+        U: Send,
+        F: Fn(&Window<'_, T>) -> U + Sync,
+    {
+        let cols = self.cols;
+        if self.rows * cols == 0 {
+            return Matrix::from_vec(self.rows, cols, Vec::new());
+        }
+
+        let bands: Vec<Vec<U>> = (0..self.rows)
+            .into_par_iter()
+            .step_by(tile.rows())
+            .map(|first| {
+                let last = (first + tile.rows()).min(self.rows);
+                let mut band: Vec<Option<U>> = (first..last)
+                    .flat_map(|_| (0..cols).map(|_| None))
+                    .collect();
+                for c0 in (0..cols).step_by(tile.cols()) {
+                    for row in first..last {
+                        for col in c0..(c0 + tile.cols()).min(cols) {
+                            let window = Window {
+                                grid: self,
+                                row,
+                                col,
+                                boundary,
+                            };
+                            band[(row - first) * cols + col] = Some(f(&window));
+                        }
+                    }
+                }
+                band.into_iter()
+                    .map(|v| v.expect("every cell is visited"))
+                    .collect()
+            })
+            .collect();
+        Matrix::from_vec(self.rows, cols, bands.into_iter().flatten().collect())
+    }
+}
+
+macro_rules! impl_convolve {
+    ($t:ty) => {
+        impl Matrix<$t> {
+            /// 串行的二维卷积，作为 [`par_convolve`](Self::par_convolve) 的对照。
+            ///
+            /// # Panics
+            ///
+            /// 参见 [`par_convolve`](Self::par_convolve)。
+            pub fn serial_convolve(
+                &self,
+                kernel: &Matrix<$t>,
+                boundary: Boundary<$t>,
+            ) -> Matrix<$t> {
+                assert_odd_kernel(kernel);
+                self.serial_stencil(boundary, |w| convolve_at(w, kernel))
+            }
+
+            /// 分块并行的二维卷积（核会被翻转，对于对称的核与互相关相同），
+            /// 每个输出元素的累加顺序固定，结果与串行版本逐位相同。
+            ///
+            /// # 示例
+            ///
+            /// ```
+            /// use parallel::matrix::{Boundary, Matrix, Tile};
+            ///
+            /// let image = Matrix::from_fn(4, 4, |r, c| if (r, c) == (1, 1) { 9.0_f64 } else { 0.0 });
+            /// let blur = Matrix::filled(3, 3, 1.0 / 9.0);
+            ///
+            /// let out = image.par_convolve(&blur, Boundary::Constant(0.0), Tile::default());
+            /// assert!((out[(0, 0)] - 1.0).abs() < 1e-12);
+            /// assert_eq!(out[(3, 3)], 0.0);
+            /// ```
+            ///
+            /// # Panics
+            ///
+            /// 核的行数或列数不是奇数时 panic。
+            pub fn par_convolve(
+                &self,
+                kernel: &Matrix<$t>,
+                boundary: Boundary<$t>,
+                tile: Tile,
+            ) -> Matrix<$t> {
+                assert_odd_kernel(kernel);
+                self.par_stencil(boundary, tile, |w| convolve_at(w, kernel))
+            }
+        }
+    };
+}
+
+impl_convolve!(f32);
+impl_convolve!(f64);
+
+fn assert_odd_kernel<T>(kernel: &Matrix<T>) {
+    assert!(
+        kernel.rows % 2 == 1 && kernel.cols % 2 == 1,
+        "matrix: convolution kernel must have odd dimensions"
+    );
+}
+
+fn convolve_at<T>(window: &Window<'_, T>, kernel: &Matrix<T>) -> T
+where
+    T: Copy + Default + Add<Output = T> + Mul<Output = T>,
+{
+    let (cy, cx) = ((kernel.rows / 2) as isize, (kernel.cols / 2) as isize);
+    let mut sum = T::default();
+    for i in 0..kernel.rows {
+        for j in 0..kernel.cols {
+            let value = window.get(cy - i as isize, cx - j as isize);
+            sum = sum + kernel.data[i * kernel.cols + j] * value;
+        }
+    }
+    sum
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    const SHAPES: [(usize, usize); 6] = [(0, 0), (1, 1), (1, 7), (7, 1), (13, 17), (130, 70)];
+    const TILES: [Tile; 3] = [Tile::new(1, 1), Tile::new(5, 3), Tile::new(64, 64)];
+
+    fn random(rows: usize, cols: usize) -> Matrix<f64> {
+        let mut rng = thread_rng();
+        Matrix::from_fn(rows, cols, |_, _| rng.gen_range(-10.0..10.0))
+    }
+
+    /// 测试逐元素运算与逐个计算一致
+    #[test]
+    fn element_wise() {
+        let a = Matrix::from_fn(30, 40, |r, c| (r * 40 + c) as i64);
+        let b = Matrix::from_fn(30, 40, |r, c| r as i64 - c as i64);
+
+        let sum = a.par_add(&b);
+        let diff = a.par_sub(&b);
+        let product = a.par_hadamard(&b);
+        for r in 0..30 {
+            for c in 0..40 {
+                assert_eq!(sum[(r, c)], a[(r, c)] + b[(r, c)]);
+                assert_eq!(diff[(r, c)], a[(r, c)] - b[(r, c)]);
+                assert_eq!(product[(r, c)], a[(r, c)] * b[(r, c)]);
+            }
+        }
+
+        let mut m = a.clone();
+        m.par_map_in_place(|x| *x -= 1);
+        assert_eq!(m, a.par_map(|x| x - 1));
+        assert_eq!(a.get(30, 0), None);
+    }
+
+    /// 测试转置与串行版本一致，且两次转置还原
+    #[test]
+    fn transpose_matches_serial() {
+        for (rows, cols) in SHAPES {
+            let m = random(rows, cols);
+            let expected = m.serial_transpose();
+            assert_eq!((expected.rows(), expected.cols()), (cols, rows));
+            for tile in TILES {
+                let t = m.par_transpose_tiled(tile);
+                assert_eq!(t, expected);
+                assert_eq!(t.par_transpose_tiled(tile), m);
+            }
+        }
+    }
+
+    /// 测试分块矩阵乘法与串行版本逐位一致
+    #[test]
+    fn matmul_matches_serial() {
+        for (rows, inner) in SHAPES {
+            for cols in [0, 1, 9, 65] {
+                let a = random(rows, inner);
+                let b = random(inner, cols);
+                let expected = a.serial_matmul(&b);
+                for tile in TILES {
+                    let c = a.par_matmul_tiled(&b, tile);
+                    let bits = |m: &Matrix<f64>| {
+                        m.as_slice().iter().map(|x| x.to_bits()).collect::<Vec<_>>()
+                    };
+                    assert_eq!(bits(&c), bits(&expected), "{rows}x{inner}x{cols} {tile:?}");
+                }
+            }
+        }
+
+        let identity = Matrix::from_fn(3, 3, |r, c| i32::from(r == c));
+        let m = Matrix::from_fn(3, 3, |r, c| (r * 3 + c) as i32);
+        assert_eq!(m.par_matmul(&identity), m);
+    }
+
+    /// 测试模板运算在各种边界条件和块大小下与串行版本一致
+    #[test]
+    fn stencil_and_convolution_match_serial() {
+        let kernel = random(3, 5);
+        for (rows, cols) in SHAPES {
+            let grid = random(rows, cols);
+            for boundary in [Boundary::Clamp, Boundary::Wrap, Boundary::Constant(0.5)] {
+                let expected = grid.serial_convolve(&kernel, boundary);
+                for tile in TILES {
+                    assert_eq!(grid.par_convolve(&kernel, boundary, tile), expected);
+                }
+
+                let neighbours = |w: &Window<'_, f64>| w.get(-1, 0) + w.get(1, 0) + w.get(0, -2);
+                assert_eq!(
+                    grid.par_stencil(boundary, Tile::new(3, 4), neighbours),
+                    grid.serial_stencil(boundary, neighbours)
+                );
+            }
+        }
+    }
+
+    /// 测试边界条件的取值
+    #[test]
+    fn boundaries() {
+        let grid = Matrix::from_fn(2, 3, |r, c| (r * 3 + c) as i32);
+        let corner = |boundary| Window {
+            grid: &grid,
+            row: 0,
+            col: 0,
+            boundary,
+        };
+
+        assert_eq!(corner(Boundary::Clamp).get(-1, -1), 0);
+        assert_eq!(corner(Boundary::Wrap).get(-1, -1), 5);
+        assert_eq!(corner(Boundary::Constant(-7)).get(-1, 0), -7);
+        assert_eq!(corner(Boundary::Wrap).get(3, 4), 4);
+    }
+
+    /// 测试为 0 的块大小按 1 处理，各个分块运算仍然正确
+    #[test]
+    fn zero_tile() {
+        let zero = Tile::new(0, 0);
+        assert_eq!((zero.rows(), zero.cols()), (1, 1));
+        assert_eq!(Tile::new(0, 7), Tile::new(1, 7));
+
+        let m = random(9, 6);
+        assert_eq!(m.par_transpose_tiled(zero), m.serial_transpose());
+        let b = random(6, 4);
+        assert_eq!(m.par_matmul_tiled(&b, zero), m.serial_matmul(&b));
+        let kernel = random(3, 3);
+        assert_eq!(
+            m.par_convolve(&kernel, Boundary::Wrap, zero),
+            m.serial_convolve(&kernel, Boundary::Wrap)
+        );
+    }
+
+    /// 测试形状不匹配时 panic
+    #[test]
+    #[should_panic(expected = "cannot multiply")]
+    fn matmul_shape_mismatch() {
+        random(2, 3).par_matmul(&random(2, 3));
+    }
+}
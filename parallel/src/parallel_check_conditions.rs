@@ -4,7 +4,7 @@ use rayon::prelude::*;
 ///
 /// # 参数
 ///
-/// * `vec` - 一个引用，指向要处理的 i32 数组。
+/// * `vec` - 一个引用，指向要处理的数组，元素可以是任意 `T: Sync` 类型。
 /// * `condition` - 一个闭包，用于检查条件。
 ///
 /// # 返回值
@@ -27,18 +27,19 @@ use rayon::prelude::*;
 ///
 /// assert!(parallel_any_condition(&vec_with_odd, |n| (*n % 2) != 0));
 /// ```
-pub fn parallel_any_condition<F>(vec: &[i32], condition: F) -> bool
+pub fn parallel_any_condition<T, F>(vec: &[T], condition: F) -> bool
 where
-    F: Fn(&i32) -> bool + Sync,
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
 {
-    vec.par_iter().any(|&n| condition(&n))
+    vec.par_iter().any(&condition)
 }
 
 /// 并行地检查数组中的所有元素是否符合条件。
 ///
 /// # 参数
 ///
-/// * `vec` - 一个引用，指向要处理的 i32 数组。
+/// * `vec` - 一个引用，指向要处理的数组，元素可以是任意 `T: Sync` 类型。
 /// * `condition` - 一个闭包，用于检查条件。
 ///
 /// # 返回值
@@ -61,11 +62,127 @@ where
 ///
 /// assert!(!parallel_all_condition(&vec_with_odd, |n| (*n % 2) == 0));
 /// ```
-pub fn parallel_all_condition<F>(vec: &[i32], condition: F) -> bool
+pub fn parallel_all_condition<T, F>(vec: &[T], condition: F) -> bool
 where
-    F: Fn(&i32) -> bool + Sync,
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
 {
-    vec.par_iter().all(|&n| condition(&n))
+    vec.par_iter().all(&condition)
+}
+
+/// 并行查找第一个（下标最小的）符合条件的元素。
+///
+/// 找到候选后，下标更大的部分会提前停止搜索，但仍可能比串行查找多检查一些元素。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_check_conditions::par_find_first;
+///
+/// let words = ["kiwi", "banana", "apple", "cherry"];
+/// assert_eq!(par_find_first(&words, |w| w.len() > 5), Some(&"banana"));
+/// assert_eq!(par_find_first(&words, |w| w.is_empty()), None);
+/// ```
+pub fn par_find_first<T, F>(vec: &[T], condition: F) -> Option<&T>
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter().find_first(|n| condition(n))
+}
+
+/// 并行查找任意一个符合条件的元素，不保证是哪一个。
+///
+/// 只需要知道存在某个匹配元素时比 [`par_find_first`] 更快，找到后所有线程立即停止。
+pub fn par_find_any<T, F>(vec: &[T], condition: F) -> Option<&T>
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter().find_any(|n| condition(n))
+}
+
+/// 并行查找第一个符合条件的元素的下标。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_check_conditions::par_position;
+///
+/// let temps = [18.5, 21.0, 35.2, 19.0, 36.1];
+/// assert_eq!(par_position(&temps, |&t| t > 35.0), Some(2));
+/// ```
+pub fn par_position<T, F>(vec: &[T], condition: F) -> Option<usize>
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter().position_first(&condition)
+}
+
+/// 并行收集所有符合条件的元素的下标，按升序返回。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_check_conditions::par_positions;
+///
+/// let vec = [3, 8, 1, 6, 7];
+/// assert_eq!(par_positions(&vec, |n| n % 2 == 0), [1, 3]);
+/// ```
+pub fn par_positions<T, F>(vec: &[T], condition: F) -> Vec<usize>
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter()
+        .enumerate()
+        .filter(|(_, n)| condition(n))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// 并行统计符合条件的元素个数。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_check_conditions::par_count_where;
+///
+/// let vec: Vec<u32> = (1..=100).collect();
+/// assert_eq!(par_count_where(&vec, |n| n % 3 == 0), 33);
+/// ```
+pub fn par_count_where<T, F>(vec: &[T], condition: F) -> usize
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter().filter(|n| condition(n)).count()
+}
+
+/// 并行地把元素按是否符合条件分成两组，两组都保持原有顺序。
+///
+/// # 返回值
+///
+/// 返回 `(符合条件的元素, 不符合条件的元素)`，元素以引用的形式返回，不会被克隆。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_check_conditions::par_partition;
+///
+/// let vec = [5, 12, 7, 20, 1];
+/// let (big, small) = par_partition(&vec, |&n| n >= 10);
+///
+/// assert_eq!(big, [&12, &20]);
+/// assert_eq!(small, [&5, &7, &1]);
+/// ```
+pub fn par_partition<T, F>(vec: &[T], condition: F) -> (Vec<&T>, Vec<&T>)
+where
+    T: Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    vec.par_iter().partition(|n| condition(n))
 }
 
 // 测试模块
@@ -91,21 +208,54 @@ mod tests {
         assert!(!parallel_all_condition(&vec, |n| *n <= 8));
     }
 
-    /// 测试并行处理的性能
+    /// 测试泛型版本可以用于非整数类型
+    #[test]
+    fn generic_conditions() {
+        let words = vec!["alpha".to_string(), "beta".to_string(), "gamma".to_string()];
+
+        assert!(parallel_any_condition(&words, |w| w.starts_with('b')));
+        assert!(parallel_all_condition(&words, |w| w.ends_with('a')));
+        assert!(!parallel_all_condition(&words, |w| w.len() == 5));
+    }
+
+    /// 测试查找函数与串行的迭代器方法结果一致
     #[test]
-    fn test_performance() {
-        let large_array: Vec<i32> = (0..1_000_000).collect();
-
-        // 测试并行检查 any 条件的时间
-        let start = std::time::Instant::now();
-        parallel_any_condition(&large_array, |n| *n > 500_000);
-        let duration_any = start.elapsed();
-        println!("并行检查 any 条件时间: {duration_any:#?}");
-
-        // 测试并行检查 all 条件的时间
-        let start = std::time::Instant::now();
-        parallel_all_condition(&large_array, |n| *n <= 1_000_000);
-        let duration_all = start.elapsed();
-        println!("并行检查 all 条件时间: {duration_all:#?}");
+    fn search_matches_serial() {
+        let vec: Vec<u64> = (0..100_000).map(|i| (i * 7919) % 10_007).collect();
+
+        for target in [0, 1, 5_000, 10_006, 20_000] {
+            let condition = |n: &u64| *n == target;
+
+            assert_eq!(
+                par_find_first(&vec, condition),
+                vec.iter().find(|n| condition(n))
+            );
+            assert_eq!(
+                par_position(&vec, condition),
+                vec.iter().position(condition)
+            );
+            assert_eq!(
+                par_count_where(&vec, condition),
+                vec.iter().filter(|n| condition(n)).count()
+            );
+
+            let positions: Vec<usize> = (0..vec.len()).filter(|&i| condition(&vec[i])).collect();
+            assert_eq!(par_positions(&vec, condition), positions);
+
+            match par_find_any(&vec, condition) {
+                Some(found) => assert!(condition(found)),
+                None => assert!(positions.is_empty()),
+            }
+        }
+    }
+
+    /// 测试分组保持原有顺序
+    #[test]
+    fn partition_preserves_order() {
+        let vec: Vec<i32> = (0..50_000).map(|i| (i * 37) % 1_001 - 500).collect();
+        let (negative, rest) = par_partition(&vec, |&n| n < 0);
+
+        let expected: (Vec<&i32>, Vec<&i32>) = vec.iter().partition(|&&n| n < 0);
+        assert_eq!((negative, rest), expected);
     }
 }
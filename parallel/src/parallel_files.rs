@@ -0,0 +1,484 @@
+//! 并行遍历目录并逐个处理文件。
+//!
+//! [`FileWalker`] 在 rayon 线程池中遍历目录树，每发现一个文件就把它作为一个任务交给线程池处理，
+//! 遍历和处理同时进行。单个文件或目录出错不会中止整批任务，所有错误连同路径一起收集到
+//! [`Batch::errors`] 中。
+
+use std::{
+    collections::HashSet,
+    fmt, fs,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use sha2::{Digest, Sha256};
+
+/// 处理某个路径时出现的错误。
+#[derive(Debug)]
+pub struct FileError {
+    /// 出错的文件或目录
+    pub path: PathBuf,
+    /// 底层的 I/O 错误
+    pub error: io::Error,
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// 一批文件的处理结果：成功的结果和所有错误。
+#[derive(Debug)]
+pub struct Batch<R> {
+    /// 每个成功处理的文件及其结果
+    pub results: Vec<(PathBuf, R)>,
+    /// 遍历或处理过程中出现的所有错误
+    pub errors: Vec<FileError>,
+}
+
+impl<R> Batch<R> {
+    /// 是否没有任何错误。
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 没有错误时返回全部结果，否则返回全部错误。
+    pub fn into_result(self) -> Result<Vec<(PathBuf, R)>, Vec<FileError>> {
+        if self.errors.is_empty() {
+            Ok(self.results)
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+type PathFilter = Box<dyn Fn(&Path) -> bool + Send + Sync>;
+
+/// 并行的目录遍历器。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_files::{count_lines, FileWalker};
+///
+/// let batch = FileWalker::new(env!("CARGO_MANIFEST_DIR"))
+///     .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+///     .run(count_lines);
+///
+/// assert!(batch.is_ok());
+/// assert!(batch.results.iter().any(|(path, _)| path.ends_with("src/lib.rs")));
+/// // 默认按路径排序
+/// assert!(batch.results.windows(2).all(|w| w[0].0 < w[1].0));
+/// ```
+pub struct FileWalker {
+    root: PathBuf,
+    ordered: bool,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    filter: Option<PathFilter>,
+}
+
+impl FileWalker {
+    /// 创建从 `root` 开始遍历的遍历器；`root` 本身是文件时只处理该文件。
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FileWalker {
+            root: root.into(),
+            ordered: true,
+            follow_links: false,
+            max_depth: None,
+            filter: None,
+        }
+    }
+
+    /// 结果和错误是否按路径排序，默认为 `true`。
+    ///
+    /// 为 `false` 时按完成的先后顺序返回，省去排序的开销，但每次运行的顺序可能不同。
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    /// 是否跟随符号链接，默认为 `false`，此时符号链接会被跳过。
+    ///
+    /// 跟随符号链接时，每个目录只会被访问一次，因此链接成环不会导致死循环。
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// 只处理距 `root` 至多 `depth` 层的文件，`root` 下的直接子项为第 1 层。
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// 只处理 `filter` 返回 `true` 的文件，不影响对目录的遍历。
+    pub fn filter<P>(mut self, filter: P) -> Self
+    where
+        P: Fn(&Path) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// 遍历目录，并在当前的 rayon 线程池中对每个文件执行 `f`。
+    ///
+    /// # 参数
+    ///
+    /// * `f` - 对单个文件的处理，返回的错误会被收集而不会中止其他文件的处理。
+    ///
+    /// # 返回值
+    ///
+    /// 所有成功的结果和所有错误，包括无法读取的目录。
+    pub fn run<R, F>(&self, f: F) -> Batch<R>
+    where
+        R: Send,
+        F: Fn(&Path) -> io::Result<R> + Sync,
+    {
+        let walk = Walk {
+            walker: self,
+            f: &f,
+            results: Mutex::new(Vec::new()),
+            errors: Mutex::new(Vec::new()),
+            visited: Mutex::new(HashSet::new()),
+        };
+        rayon::scope(|scope| walk.visit(scope, self.root.clone(), 0));
+
+        let mut batch = Batch {
+            results: walk.results.into_inner().unwrap_or_else(|e| e.into_inner()),
+            errors: walk.errors.into_inner().unwrap_or_else(|e| e.into_inner()),
+        };
+        if self.ordered {
+            batch.results.sort_by(|a, b| a.0.cmp(&b.0));
+            batch.errors.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        batch
+    }
+}
+
+/// 一次遍历的共享状态。
+struct Walk<'a, R, F> {
+    walker: &'a FileWalker,
+    f: &'a F,
+    results: Mutex<Vec<(PathBuf, R)>>,
+    errors: Mutex<Vec<FileError>>,
+    /// 跟随符号链接时已访问过的目录（规范化后的路径）
+    visited: Mutex<HashSet<PathBuf>>,
+}
+
+impl<R, F> Walk<'_, R, F>
+where
+    R: Send,
+    F: Fn(&Path) -> io::Result<R> + Sync,
+{
+    fn visit<'s>(&'s self, scope: &rayon::Scope<'s>, path: PathBuf, depth: usize) {
+        // 根路径总是跟随符号链接，以便传入指向目录的链接
+        let metadata = if self.walker.follow_links || depth == 0 {
+            fs::metadata(&path)
+        } else {
+            fs::symlink_metadata(&path)
+        };
+        let metadata = match metadata {
+            Ok(metadata) => metadata,
+            Err(error) => return self.error(path, error),
+        };
+
+        if metadata.is_file() {
+            if self
+                .walker
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter(&path))
+            {
+                match (self.f)(&path) {
+                    Ok(value) => lock(&self.results).push((path, value)),
+                    Err(error) => self.error(path, error),
+                }
+            }
+        } else if metadata.is_dir() {
+            if self.walker.max_depth.is_some_and(|max| depth >= max) {
+                return;
+            }
+            if self.walker.follow_links {
+                match fs::canonicalize(&path) {
+                    Ok(canonical) => {
+                        if !lock(&self.visited).insert(canonical) {
+                            return;
+                        }
+                    }
+                    Err(error) => return self.error(path, error),
+                }
+            }
+
+            let entries = match fs::read_dir(&path) {
+                Ok(entries) => entries,
+                Err(error) => return self.error(path, error),
+            };
+            for entry in entries {
+                match entry {
+                    Ok(entry) => {
+                        let child = entry.path();
+                        scope.spawn(move |scope| self.visit(scope, child, depth + 1));
+                    }
+                    Err(error) => self.error(path.clone(), error),
+                }
+            }
+        }
+        // 未跟随的符号链接和其他特殊文件被跳过
+    }
+
+    fn error(&self, path: PathBuf, error: io::Error) {
+        lock(&self.errors).push(FileError { path, error });
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 计算文件内容的 SHA-256，返回小写十六进制字符串。
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0; 16 * 1024];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// 统计文件的行数；最后一行没有换行符时也计为一行。
+pub fn count_lines(path: &Path) -> io::Result<usize> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut lines = 0;
+    let mut last = b'\n';
+    loop {
+        let buffer = reader.fill_buf()?;
+        let Some(&end) = buffer.last() else {
+            break;
+        };
+        lines += buffer.iter().filter(|&&b| b == b'\n').count();
+        last = end;
+        let len = buffer.len();
+        reader.consume(len);
+    }
+    Ok(lines + usize::from(last != b'\n'))
+}
+
+/// 文件中与模式匹配的一行。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    /// 行号，从 1 开始
+    pub line_number: usize,
+    /// 去掉行尾换行符后的内容，无效的 UTF-8 被替换为 U+FFFD
+    pub line: String,
+}
+
+/// 找出文件中包含 `pattern` 的所有行。
+pub fn grep_file(path: &Path, pattern: &str) -> io::Result<Vec<GrepMatch>> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut matches = Vec::new();
+    let mut buffer = Vec::new();
+    let mut line_number = 0;
+    while reader.read_until(b'\n', &mut buffer)? > 0 {
+        line_number += 1;
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.contains(pattern) {
+            matches.push(GrepMatch {
+                line_number,
+                line: line.to_owned(),
+            });
+        }
+        buffer.clear();
+    }
+    Ok(matches)
+}
+
+/// 并行计算 `root` 下所有文件的 SHA-256，按路径排序。
+pub fn par_hash_files(root: impl Into<PathBuf>) -> Batch<String> {
+    FileWalker::new(root).run(hash_file)
+}
+
+/// 并行统计 `root` 下所有文件的行数，按路径排序。
+pub fn par_count_lines(root: impl Into<PathBuf>) -> Batch<usize> {
+    FileWalker::new(root).run(count_lines)
+}
+
+/// 在 `root` 下的所有文件中并行查找包含 `pattern` 的行，按路径排序，只保留有匹配的文件。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_files::par_grep;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// std::fs::write(dir.path().join("a.txt"), "todo: tests\ndone\n").unwrap();
+/// std::fs::write(dir.path().join("b.txt"), "nothing here\n").unwrap();
+///
+/// let batch = par_grep(dir.path(), "todo");
+/// assert_eq!(batch.results.len(), 1);
+/// assert!(batch.results[0].0.ends_with("a.txt"));
+/// assert_eq!(batch.results[0].1[0].line, "todo: tests");
+/// ```
+pub fn par_grep(root: impl Into<PathBuf>, pattern: &str) -> Batch<Vec<GrepMatch>> {
+    let mut batch = FileWalker::new(root).run(|path| grep_file(path, pattern));
+    batch.results.retain(|(_, matches)| !matches.is_empty());
+    batch
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// 创建一个包含多层目录的测试目录树
+    fn sample_tree() -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a/b/c")).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::write(root.join("top.txt"), "alpha\nbeta\n").unwrap();
+        fs::write(root.join("a/one.txt"), "needle here\nnothing\nneedle again").unwrap();
+        fs::write(root.join("a/b/two.log"), "").unwrap();
+        fs::write(root.join("a/b/c/three.txt"), b"x\xff\nneedle\r\n").unwrap();
+        for i in 0..50 {
+            fs::write(root.join(format!("a/b/file{i:02}.txt")), "1\n2\n3\n").unwrap();
+        }
+        dir
+    }
+
+    /// 测试有序收集时结果按路径排序，且与无序收集的结果集合相同
+    #[test]
+    fn ordered_and_unordered() {
+        let dir = sample_tree();
+        let ordered = FileWalker::new(dir.path()).run(count_lines);
+        assert!(ordered.is_ok());
+        assert_eq!(ordered.results.len(), 54);
+        assert!(ordered.results.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let mut unordered = FileWalker::new(dir.path()).ordered(false).run(count_lines);
+        unordered.results.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(unordered.results, ordered.results);
+
+        let lines = |name: &str| {
+            ordered
+                .results
+                .iter()
+                .find(|(path, _)| path.ends_with(name))
+                .map(|&(_, n)| n)
+        };
+        assert_eq!(lines("top.txt"), Some(2));
+        assert_eq!(lines("a/one.txt"), Some(3));
+        assert_eq!(lines("a/b/two.log"), Some(0));
+        assert_eq!(lines("a/b/c/three.txt"), Some(2));
+    }
+
+    /// 测试过滤器和最大深度
+    #[test]
+    fn filter_and_depth() {
+        let dir = sample_tree();
+        let batch = FileWalker::new(dir.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+            .run(|_| Ok(()));
+        assert_eq!(batch.results.len(), 1);
+
+        let batch = FileWalker::new(dir.path()).max_depth(2).run(|_| Ok(()));
+        let names: Vec<_> = batch
+            .results
+            .iter()
+            .map(|(p, _)| p.strip_prefix(dir.path()).unwrap())
+            .collect();
+        assert_eq!(names, [Path::new("a/one.txt"), Path::new("top.txt")]);
+    }
+
+    /// 测试单个文件出错不会中止其他文件，错误带有路径
+    #[test]
+    fn errors_are_aggregated() {
+        let dir = sample_tree();
+        let batch = FileWalker::new(dir.path()).run(|path| {
+            if path.to_string_lossy().contains("file1") {
+                Err(io::Error::other("rejected"))
+            } else {
+                count_lines(path)
+            }
+        });
+
+        assert_eq!(batch.results.len(), 44);
+        assert_eq!(batch.errors.len(), 10);
+        assert!(batch.errors.windows(2).all(|w| w[0].path < w[1].path));
+        assert!(batch.errors[0]
+            .to_string()
+            .ends_with("file10.txt: rejected"));
+        assert!(batch.into_result().is_err());
+
+        let missing = FileWalker::new(dir.path().join("missing")).run(count_lines);
+        assert!(missing.results.is_empty());
+        assert_eq!(missing.errors[0].error.kind(), io::ErrorKind::NotFound);
+    }
+
+    /// 测试哈希和查找的结果
+    #[test]
+    fn hash_and_grep() {
+        let dir = sample_tree();
+        let hashes = par_hash_files(dir.path());
+        let empty = hashes
+            .results
+            .iter()
+            .find(|(path, _)| path.ends_with("two.log"))
+            .unwrap();
+        assert_eq!(
+            empty.1,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let batch = par_grep(dir.path(), "needle");
+        assert!(batch.is_ok());
+        let found: Vec<(usize, &str)> = batch
+            .results
+            .iter()
+            .flat_map(|(_, matches)| matches.iter().map(|m| (m.line_number, m.line.as_str())))
+            .collect();
+        // a/b/c/three.txt 按路径排在 a/one.txt 之前
+        assert_eq!(
+            found,
+            [(2, "needle"), (1, "needle here"), (3, "needle again")]
+        );
+    }
+
+    /// 测试跟随符号链接时不会因为链接成环而死循环
+    #[cfg(unix)]
+    #[test]
+    fn symlink_cycles() {
+        let dir = sample_tree();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("a/loop")).unwrap();
+        std::os::unix::fs::symlink(dir.path().join("top.txt"), dir.path().join("link.txt"))
+            .unwrap();
+
+        let skipped = FileWalker::new(dir.path()).run(|_| Ok(()));
+        assert_eq!(skipped.results.len(), 54);
+
+        let followed = FileWalker::new(dir.path())
+            .follow_links(true)
+            .run(|_| Ok(()));
+        assert!(followed.is_ok());
+        assert_eq!(followed.results.len(), 55);
+    }
+}
@@ -0,0 +1,405 @@
+//! 基于哈希的并行分组与聚合。
+//!
+//! 每个 rayon 任务把自己负责的连续一段记录折叠进本地的 `HashMap`，任务之间不共享任何状态，
+//! 最后按输入顺序两两合并这些局部表。整个过程不对记录排序；只有在要求确定顺序时，
+//! 才对（通常远少于记录数的）分组按首次出现的位置排序。
+
+use std::{collections::HashMap, hash::Hash, ops::Add};
+
+use rayon::prelude::*;
+
+/// 分组结果的排列顺序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupOrder {
+    /// 哈希表的迭代顺序，每次运行都可能不同，但省去了排序
+    #[default]
+    Arbitrary,
+    /// 按每组第一条记录在输入中的位置排列，结果与串行遍历得到的顺序相同
+    FirstSeen,
+}
+
+/// 分组键到（首次出现的下标，聚合值）的映射。
+type LocalMap<K, A> = HashMap<K, (usize, A)>;
+
+fn into_ordered<K, A>(map: LocalMap<K, A>, order: GroupOrder) -> Vec<(K, A)> {
+    let mut groups: Vec<(K, (usize, A))> = map.into_iter().collect();
+    if order == GroupOrder::FirstSeen {
+        groups.sort_unstable_by_key(|(_, (first, _))| *first);
+    }
+    groups.into_iter().map(|(k, (_, acc))| (k, acc)).collect()
+}
+
+/// 串行的分组聚合，作为 [`par_aggregate_by_key`] 的对照。
+pub fn serial_aggregate_by_key<'a, T, K, A, KF, I, F>(
+    slice: &'a [T],
+    key: KF,
+    init: I,
+    fold: F,
+    order: GroupOrder,
+) -> Vec<(K, A)>
+where
+    K: Eq + Hash,
+    KF: Fn(&T) -> K,
+    I: Fn() -> A,
+    F: Fn(&mut A, &'a T),
+{
+    let mut map = LocalMap::new();
+    for (i, item) in slice.iter().enumerate() {
+        let (_, acc) = map.entry(key(item)).or_insert_with(|| (i, init()));
+        fold(acc, item);
+    }
+    into_ordered(map, order)
+}
+
+/// 并行地按 `key` 分组，并把每组的记录聚合为一个值。
+///
+/// 每组的聚合值从 `init()` 开始，依次用 `fold` 并入该组的记录；不同任务得到的同组聚合值再用
+/// `merge(左, 右)` 合并，左侧总是来自输入中靠前的部分。因此只要 `fold` 与 `merge` 满足结合律，
+/// 结果就与串行聚合相同（浮点数的求和可能有舍入上的差异）。
+///
+/// # 参数
+///
+/// * `slice` - 一个引用，指向要分组的记录。
+/// * `key` - 计算记录的分组键。
+/// * `init` - 创建空的聚合值。
+/// * `fold` - 把一条记录并入聚合值。
+/// * `merge` - 把右侧的聚合值并入左侧。
+/// * `order` - 结果的排列顺序，参见 [`GroupOrder`]。
+///
+/// # 返回值
+///
+/// 每组一个 `(键, 聚合值)`。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_group::{par_aggregate_by_key, GroupOrder};
+///
+/// let words = ["apple", "bob", "avocado", "cat", "banana"];
+/// // 按首字母统计总长度
+/// let lengths = par_aggregate_by_key(
+///     &words,
+///     |w| w.as_bytes()[0],
+///     || 0,
+///     |sum, w| *sum += w.len(),
+///     |sum, other| *sum += other,
+///     GroupOrder::FirstSeen,
+/// );
+/// assert_eq!(lengths, [(b'a', 12), (b'b', 9), (b'c', 3)]);
+/// ```
+pub fn par_aggregate_by_key<'a, T, K, A, KF, I, F, M>(
+    slice: &'a [T],
+    key: KF,
+    init: I,
+    fold: F,
+    merge: M,
+    order: GroupOrder,
+) -> Vec<(K, A)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    A: Send,
+    KF: Fn(&T) -> K + Sync + Send,
+    I: Fn() -> A + Sync + Send,
+    F: Fn(&mut A, &'a T) + Sync + Send,
+    M: Fn(&mut A, A) + Sync + Send,
+{
+    let map = slice
+        .par_iter()
+        .enumerate()
+        .fold(LocalMap::new, |mut local, (i, item)| {
+            let (_, acc) = local.entry(key(item)).or_insert_with(|| (i, init()));
+            fold(acc, item);
+            local
+        })
+        .reduce(LocalMap::new, |left, right| {
+            // 把较小的表并入较大的表，同时保持左侧的聚合值在前
+            let (mut earlier, mut later) = (left, right);
+            let swapped = earlier.len() < later.len();
+            if swapped {
+                std::mem::swap(&mut earlier, &mut later);
+            }
+            for (k, (first, acc)) in later {
+                match earlier.remove(&k) {
+                    None => {
+                        earlier.insert(k, (first, acc));
+                    }
+                    // 交换后 `earlier` 中的聚合值来自右侧，需要并入左侧的值之后
+                    Some((other_first, other)) if swapped => {
+                        let mut acc = acc;
+                        merge(&mut acc, other);
+                        earlier.insert(k, (first.min(other_first), acc));
+                    }
+                    Some((other_first, mut other)) => {
+                        merge(&mut other, acc);
+                        earlier.insert(k, (first.min(other_first), other));
+                    }
+                }
+            }
+            earlier
+        });
+    into_ordered(map, order)
+}
+
+/// 并行地按 `key` 分组，每组保存指向其记录的引用，组内按输入顺序排列。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_group::{par_group_by_key, GroupOrder};
+///
+/// let numbers: Vec<u32> = (1..=10).collect();
+/// let groups = par_group_by_key(&numbers, |n| n % 3, GroupOrder::FirstSeen);
+///
+/// assert_eq!(groups[0], (1, vec![&1, &4, &7, &10]));
+/// assert_eq!(groups[2], (0, vec![&3, &6, &9]));
+/// ```
+pub fn par_group_by_key<T, K, KF>(slice: &[T], key: KF, order: GroupOrder) -> Vec<(K, Vec<&T>)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+{
+    par_aggregate_by_key(
+        slice,
+        key,
+        Vec::new,
+        |group, item| group.push(item),
+        |group, later| group.extend(later),
+        order,
+    )
+}
+
+/// 并行地统计每组的记录数。
+pub fn par_count_by_key<T, K, KF>(slice: &[T], key: KF, order: GroupOrder) -> Vec<(K, usize)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+{
+    par_aggregate_by_key(
+        slice,
+        key,
+        || 0,
+        |count, _| *count += 1,
+        |count, other| *count += other,
+        order,
+    )
+}
+
+/// 并行地对每组记录的 `value` 求和。
+///
+/// 对于浮点数，任务的划分会影响求和顺序，结果可能在最后几位上与串行求和不同。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_group::{par_sum_by_key, GroupOrder};
+///
+/// let sales = [("north", 3), ("south", 5), ("north", 4)];
+/// let totals = par_sum_by_key(&sales, |s| s.0, |s| s.1, GroupOrder::FirstSeen);
+/// assert_eq!(totals, [("north", 7), ("south", 5)]);
+/// ```
+pub fn par_sum_by_key<T, K, V, KF, VF>(
+    slice: &[T],
+    key: KF,
+    value: VF,
+    order: GroupOrder,
+) -> Vec<(K, V)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    V: Copy + Default + Add<Output = V> + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+    VF: Fn(&T) -> V + Sync + Send,
+{
+    par_aggregate_by_key(
+        slice,
+        key,
+        V::default,
+        |sum, item| *sum = *sum + value(item),
+        |sum, other| *sum = *sum + other,
+        order,
+    )
+}
+
+/// `keep_b(当前值, v)` 为真时用 `v` 替换 `acc`；无法与自身比较的值（如 NaN）被忽略。
+fn pick<V: PartialOrd + Copy>(acc: &mut Option<V>, v: Option<V>, keep_b: fn(&V, &V) -> bool) {
+    let Some(v) = v.filter(|v| v.partial_cmp(v).is_some()) else {
+        return;
+    };
+    match acc {
+        Some(current) if !keep_b(current, &v) => {}
+        _ => *acc = Some(v),
+    }
+}
+
+fn par_extreme_by_key<T, K, V, KF, VF>(
+    slice: &[T],
+    key: KF,
+    value: VF,
+    order: GroupOrder,
+    keep_b: fn(&V, &V) -> bool,
+) -> Vec<(K, V)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    V: PartialOrd + Copy + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+    VF: Fn(&T) -> V + Sync + Send,
+{
+    par_aggregate_by_key(
+        slice,
+        key,
+        || None,
+        |acc, item| pick(acc, Some(value(item)), keep_b),
+        |acc, other| pick(acc, other, keep_b),
+        order,
+    )
+    .into_iter()
+    .filter_map(|(k, v)| v.map(|v| (k, v)))
+    .collect()
+}
+
+/// 并行地求每组记录的 `value` 的最小值。
+///
+/// 无法与自身比较的值（如 NaN）被忽略，只包含这类值的组不出现在结果中。
+pub fn par_min_by_key<T, K, V, KF, VF>(
+    slice: &[T],
+    key: KF,
+    value: VF,
+    order: GroupOrder,
+) -> Vec<(K, V)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    V: PartialOrd + Copy + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+    VF: Fn(&T) -> V + Sync + Send,
+{
+    par_extreme_by_key(slice, key, value, order, |current, v| v < current)
+}
+
+/// 并行地求每组记录的 `value` 的最大值，NaN 的处理与 [`par_min_by_key`] 相同。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_group::{par_max_by_key, par_min_by_key, GroupOrder};
+///
+/// let temps = [("oslo", -3.5), ("rome", 14.0), ("oslo", 2.0), ("rome", f64::NAN)];
+/// let order = GroupOrder::FirstSeen;
+///
+/// assert_eq!(par_min_by_key(&temps, |t| t.0, |t| t.1, order), [("oslo", -3.5), ("rome", 14.0)]);
+/// assert_eq!(par_max_by_key(&temps, |t| t.0, |t| t.1, order), [("oslo", 2.0), ("rome", 14.0)]);
+/// ```
+pub fn par_max_by_key<T, K, V, KF, VF>(
+    slice: &[T],
+    key: KF,
+    value: VF,
+    order: GroupOrder,
+) -> Vec<(K, V)>
+where
+    T: Sync,
+    K: Eq + Hash + Send,
+    V: PartialOrd + Copy + Send,
+    KF: Fn(&T) -> K + Sync + Send,
+    VF: Fn(&T) -> V + Sync + Send,
+{
+    par_extreme_by_key(slice, key, value, order, |current, v| v > current)
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    #[derive(Debug, PartialEq)]
+    struct Record {
+        region: u16,
+        amount: i64,
+    }
+
+    fn records(len: usize, regions: u16) -> Vec<Record> {
+        let mut rng = thread_rng();
+        (0..len)
+            .map(|_| Record {
+                region: rng.gen_range(0..regions),
+                amount: rng.gen_range(-1_000..1_000),
+            })
+            .collect()
+    }
+
+    /// 测试按首次出现排序时，分组和组内顺序都与串行结果相同
+    #[test]
+    fn group_by_matches_serial() {
+        for (len, regions) in [(0, 1), (1, 1), (1_000, 1), (100_000, 7), (100_000, 5_000)] {
+            let data = records(len, regions);
+            let expected = serial_aggregate_by_key(
+                &data,
+                |r| r.region,
+                Vec::new,
+                |group, r| group.push(r),
+                GroupOrder::FirstSeen,
+            );
+            assert_eq!(
+                par_group_by_key(&data, |r| r.region, GroupOrder::FirstSeen),
+                expected
+            );
+
+            let mut arbitrary = par_group_by_key(&data, |r| r.region, GroupOrder::Arbitrary);
+            arbitrary.sort_by_key(|(region, group)| (data.len() - group.len(), *region));
+            let mut sorted = expected;
+            sorted.sort_by_key(|(region, group)| (data.len() - group.len(), *region));
+            assert_eq!(arbitrary, sorted);
+        }
+    }
+
+    /// 测试各个聚合函数与串行计算一致
+    #[test]
+    fn aggregates_match_serial() {
+        let data = records(200_000, 300);
+        let order = GroupOrder::FirstSeen;
+        let serial = |init: fn() -> (usize, i64, i64, i64)| {
+            serial_aggregate_by_key(
+                &data,
+                |r| r.region,
+                init,
+                |(count, sum, min, max), r| {
+                    *count += 1;
+                    *sum += r.amount;
+                    *min = (*min).min(r.amount);
+                    *max = (*max).max(r.amount);
+                },
+                order,
+            )
+        };
+        let expected = serial(|| (0, 0, i64::MAX, i64::MIN));
+
+        let counts = par_count_by_key(&data, |r| r.region, order);
+        let sums = par_sum_by_key(&data, |r| r.region, |r| r.amount, order);
+        let mins = par_min_by_key(&data, |r| r.region, |r| r.amount, order);
+        let maxs = par_max_by_key(&data, |r| r.region, |r| r.amount, order);
+        for (i, (region, (count, sum, min, max))) in expected.into_iter().enumerate() {
+            assert_eq!(counts[i], (region, count));
+            assert_eq!(sums[i], (region, sum));
+            assert_eq!(mins[i], (region, min));
+            assert_eq!(maxs[i], (region, max));
+        }
+    }
+
+    /// 测试 NaN 被忽略，只有 NaN 的组不出现在结果中
+    #[test]
+    fn min_max_skip_nan() {
+        let mut data: Vec<(u8, f64)> = (0..10_000).map(|i| ((i % 3) as u8, i as f64)).collect();
+        data.push((3, f64::NAN));
+        data[0].1 = f64::NAN;
+
+        let order = GroupOrder::FirstSeen;
+        let mins = par_min_by_key(&data, |d| d.0, |d| d.1, order);
+        let maxs = par_max_by_key(&data, |d| d.0, |d| d.1, order);
+        assert_eq!(mins, [(0, 3.0), (1, 1.0), (2, 2.0)]);
+        assert_eq!(maxs, [(0, 9_999.0), (1, 9_997.0), (2, 9_998.0)]);
+    }
+}
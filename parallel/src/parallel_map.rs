@@ -0,0 +1,228 @@
+use rayon::prelude::*;
+
+/// 串行地对切片中的每个元素原地执行 `f`，作为 [`par_map_in_place`] 的对照。
+pub fn serial_map_in_place<T, F>(slice: &mut [T], f: F)
+where
+    F: Fn(&mut T),
+{
+    slice.iter_mut().for_each(f)
+}
+
+/// 并行地对切片中的每个元素原地执行 `f`。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要处理的切片。
+/// * `f` - 对单个元素的原地修改。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_map::par_map_in_place;
+///
+/// let mut names = vec!["a".to_string(), "b".to_string()];
+/// par_map_in_place(&mut names, |s| s.push('!'));
+/// assert_eq!(names, ["a!", "b!"]);
+/// ```
+pub fn par_map_in_place<T, F>(slice: &mut [T], f: F)
+where
+    T: Send,
+    F: Fn(&mut T) + Sync + Send,
+{
+    slice.par_iter_mut().for_each(f)
+}
+
+/// 与 [`par_map_in_place`] 相同，但每个并行任务至少处理 `chunk_size` 个元素。
+///
+/// 单个元素的处理越轻量，越需要较大的块来摊薄任务调度的开销；
+/// 处理代价很高或不均匀时，较小的块有利于负载均衡。`chunk_size` 为 0 时按 1 处理。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_map::par_map_in_place_chunked;
+///
+/// let mut arr: Vec<u64> = (0..10_000).collect();
+/// par_map_in_place_chunked(&mut arr, 4096, |x| *x *= 2);
+/// assert_eq!(arr[9_999], 19_998);
+/// ```
+pub fn par_map_in_place_chunked<T, F>(slice: &mut [T], chunk_size: usize, f: F)
+where
+    T: Send,
+    F: Fn(&mut T) + Sync + Send,
+{
+    slice
+        .par_iter_mut()
+        .with_min_len(chunk_size.max(1))
+        .for_each(f)
+}
+
+/// 串行地把 `f` 应用到每个元素，返回新的向量，作为 [`par_map`] 的对照。
+pub fn serial_map<T, U, F>(slice: &[T], f: F) -> Vec<U>
+where
+    F: Fn(&T) -> U,
+{
+    slice.iter().map(f).collect()
+}
+
+/// 并行地把 `f` 应用到每个元素，按原有顺序返回结果。
+///
+/// # 参数
+///
+/// * `slice` - 一个引用，指向要处理的切片。
+/// * `f` - 把单个元素转换为结果的函数。
+///
+/// # 返回值
+///
+/// 与输入等长的向量，第 `i` 个元素为 `f(&slice[i])`。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_map::par_map;
+///
+/// let words = ["apple", "kiwi", "banana"];
+/// assert_eq!(par_map(&words, |w| w.len()), [5, 4, 6]);
+/// ```
+pub fn par_map<T, U, F>(slice: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    slice.par_iter().map(f).collect()
+}
+
+/// 与 [`par_map`] 相同，但每个并行任务至少处理 `chunk_size` 个元素。
+pub fn par_map_chunked<T, U, F>(slice: &[T], chunk_size: usize, f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    slice
+        .par_iter()
+        .with_min_len(chunk_size.max(1))
+        .map(f)
+        .collect()
+}
+
+/// 串行地把 `f` 逐对应用到两个切片的元素上，作为 [`par_zip_with`] 的对照。
+///
+/// 两个切片长度不同时 panic。
+pub fn serial_zip_with<A, B, U, F>(a: &[A], b: &[B], f: F) -> Vec<U>
+where
+    F: Fn(&A, &B) -> U,
+{
+    assert_same_len(a, b);
+    a.iter().zip(b).map(|(x, y)| f(x, y)).collect()
+}
+
+/// 并行地把 `f` 逐对应用到两个等长切片的元素上，按原有顺序返回结果。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_map::par_zip_with;
+///
+/// let prices = [2.5, 4.0, 1.25];
+/// let quantities = [4, 1, 8];
+/// let totals = par_zip_with(&prices, &quantities, |p, &q| p * q as f64);
+///
+/// assert_eq!(totals, [10.0, 4.0, 10.0]);
+/// ```
+///
+/// # Panics
+///
+/// 两个切片长度不同时 panic。
+pub fn par_zip_with<A, B, U, F>(a: &[A], b: &[B], f: F) -> Vec<U>
+where
+    A: Sync,
+    B: Sync,
+    U: Send,
+    F: Fn(&A, &B) -> U + Sync + Send,
+{
+    assert_same_len(a, b);
+    a.par_iter().zip(b).map(|(x, y)| f(x, y)).collect()
+}
+
+/// 与 [`par_zip_with`] 相同，但每个并行任务至少处理 `chunk_size` 对元素。
+pub fn par_zip_with_chunked<A, B, U, F>(a: &[A], b: &[B], chunk_size: usize, f: F) -> Vec<U>
+where
+    A: Sync,
+    B: Sync,
+    U: Send,
+    F: Fn(&A, &B) -> U + Sync + Send,
+{
+    assert_same_len(a, b);
+    a.par_iter()
+        .zip(b)
+        .with_min_len(chunk_size.max(1))
+        .map(|(x, y)| f(x, y))
+        .collect()
+}
+
+fn assert_same_len<A, B>(a: &[A], b: &[B]) {
+    assert_eq!(a.len(), b.len(), "zip_with: slices have different lengths");
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试原地修改的并行版本与串行版本结果一致
+    #[test]
+    fn map_in_place_matches_serial() {
+        for len in [0, 1, 7, 10_000] {
+            let mut expected: Vec<i64> = (0..len).collect();
+            serial_map_in_place(&mut expected, |x| *x = *x * 3 - 1);
+
+            let mut arr: Vec<i64> = (0..len).collect();
+            par_map_in_place(&mut arr, |x| *x = *x * 3 - 1);
+            assert_eq!(arr, expected);
+
+            for chunk_size in [0, 1, 64, 100_000] {
+                let mut arr: Vec<i64> = (0..len).collect();
+                par_map_in_place_chunked(&mut arr, chunk_size, |x| *x = *x * 3 - 1);
+                assert_eq!(arr, expected);
+            }
+        }
+    }
+
+    /// 测试 map 保持顺序并支持不同的结果类型
+    #[test]
+    fn map_preserves_order() {
+        let input: Vec<u32> = (0..5_000).collect();
+        let expected = serial_map(&input, |x| format!("#{x}"));
+
+        assert_eq!(par_map(&input, |x| format!("#{x}")), expected);
+        for chunk_size in [1, 3, 1_000] {
+            assert_eq!(
+                par_map_chunked(&input, chunk_size, |x| format!("#{x}")),
+                expected
+            );
+        }
+    }
+
+    /// 测试 zip_with 与串行版本一致
+    #[test]
+    fn zip_with_matches_serial() {
+        let a: Vec<f64> = (0..3_000).map(|x| x as f64 * 0.5).collect();
+        let b: Vec<i32> = (0..3_000).rev().collect();
+        let expected = serial_zip_with(&a, &b, |x, &y| x * y as f64);
+
+        assert_eq!(par_zip_with(&a, &b, |x, &y| x * y as f64), expected);
+        assert_eq!(
+            par_zip_with_chunked(&a, &b, 256, |x, &y| x * y as f64),
+            expected
+        );
+    }
+
+    /// 测试长度不同的切片会 panic
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn zip_with_length_mismatch() {
+        par_zip_with(&[1, 2, 3], &[1, 2], |a, b| a + b);
+    }
+}
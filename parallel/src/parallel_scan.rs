@@ -0,0 +1,444 @@
+//! 与顺序相关的并行原语：前缀扫描、分段扫描、流压缩和去重。
+//!
+//! 扫描分三步完成：先并行求出每个块的总和，再串行地对块总和做前缀扫描得到每个块的起始值，
+//! 最后各块带着起始值并行扫描。因此运算只需满足结合律，不要求交换律，
+//! 例如字符串拼接或矩阵乘法也能得到与串行相同的结果。
+
+use rayon::prelude::*;
+
+/// 扫描时每个块的元素个数，不超过该长度的输入直接串行处理。
+const SCAN_BLOCK: usize = 4096;
+
+/// 串行的包含式扫描，作为 [`par_inclusive_scan`] 的对照。
+pub fn serial_inclusive_scan<T, F>(slice: &[T], op: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    inclusive_block(slice, None, &op).collect()
+}
+
+/// 并行的包含式扫描：`out[i] = slice[0] op slice[1] op … op slice[i]`。
+///
+/// # 参数
+///
+/// * `slice` - 输入数据。
+/// * `op` - 满足结合律的二元运算。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_inclusive_scan;
+///
+/// assert_eq!(par_inclusive_scan(&[3, 1, 4, 1, 5], |a, b| a + b), [3, 4, 8, 9, 14]);
+/// assert_eq!(par_inclusive_scan(&[3, 1, 4, 1, 5], |a, b| *a.max(b)), [3, 3, 4, 4, 5]);
+/// ```
+pub fn par_inclusive_scan<T, F>(slice: &[T], op: F) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> T + Sync,
+{
+    if slice.len() <= SCAN_BLOCK {
+        return serial_inclusive_scan(slice, op);
+    }
+
+    let carries = block_carries(slice, None, &op);
+    let mut out = Vec::with_capacity(slice.len());
+    out.par_extend(
+        slice
+            .par_chunks(SCAN_BLOCK)
+            .zip(carries)
+            .flat_map_iter(|(chunk, carry)| inclusive_block(chunk, carry, &op)),
+    );
+    out
+}
+
+/// 串行的排除式扫描，作为 [`par_exclusive_scan`] 的对照。
+pub fn serial_exclusive_scan<T, F>(slice: &[T], identity: T, op: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    exclusive_block(slice, identity, &op).collect()
+}
+
+/// 并行的排除式扫描：`out[0] = identity`，`out[i] = slice[0] op … op slice[i - 1]`。
+///
+/// # 参数
+///
+/// * `slice` - 输入数据。
+/// * `identity` - 运算的单位元，例如加法的 0、乘法的 1。
+/// * `op` - 满足结合律的二元运算。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_exclusive_scan;
+///
+/// // 每段数据在输出缓冲区中的起始位置
+/// let lengths = [3, 0, 2, 5];
+/// assert_eq!(par_exclusive_scan(&lengths, 0, |a, b| a + b), [0, 3, 3, 5]);
+/// ```
+pub fn par_exclusive_scan<T, F>(slice: &[T], identity: T, op: F) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> T + Sync,
+{
+    if slice.len() <= SCAN_BLOCK {
+        return serial_exclusive_scan(slice, identity, op);
+    }
+
+    let carries = block_carries(slice, Some(identity), &op);
+    let mut out = Vec::with_capacity(slice.len());
+    out.par_extend(
+        slice
+            .par_chunks(SCAN_BLOCK)
+            .zip(carries)
+            .flat_map_iter(|(chunk, carry)| {
+                exclusive_block(chunk, carry.expect("seeded with identity"), &op)
+            }),
+    );
+    out
+}
+
+/// 并行求出每个块之前所有元素的累积值；`seed` 为 `None` 时第一个块没有累积值。
+fn block_carries<T, F>(slice: &[T], seed: Option<T>, op: &F) -> Vec<Option<T>>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> T + Sync,
+{
+    let totals: Vec<T> = slice
+        .par_chunks(SCAN_BLOCK)
+        .map(|chunk| {
+            let (first, rest) = chunk.split_first().expect("chunks are never empty");
+            rest.iter().fold(first.clone(), |acc, x| op(&acc, x))
+        })
+        .collect();
+
+    // 块数很少，串行扫描即可
+    let mut carry = seed;
+    totals
+        .iter()
+        .map(|total| {
+            let current = carry.clone();
+            carry = Some(match carry.take() {
+                Some(acc) => op(&acc, total),
+                None => total.clone(),
+            });
+            current
+        })
+        .collect()
+}
+
+fn inclusive_block<'a, T, F>(
+    chunk: &'a [T],
+    carry: Option<T>,
+    op: &'a F,
+) -> impl Iterator<Item = T> + 'a
+where
+    T: Clone + 'a,
+    F: Fn(&T, &T) -> T,
+{
+    chunk.iter().scan(carry, move |acc, x| {
+        let value = match acc.take() {
+            Some(acc) => op(&acc, x),
+            None => x.clone(),
+        };
+        *acc = Some(value.clone());
+        Some(value)
+    })
+}
+
+fn exclusive_block<'a, T, F>(chunk: &'a [T], carry: T, op: &'a F) -> impl Iterator<Item = T> + 'a
+where
+    T: Clone + 'a,
+    F: Fn(&T, &T) -> T,
+{
+    chunk.iter().scan(carry, move |acc, x| {
+        let next = op(acc, x);
+        Some(std::mem::replace(acc, next))
+    })
+}
+
+/// 串行的分段包含式扫描，作为 [`par_segmented_scan`] 的对照。
+///
+/// # Panics
+///
+/// `slice` 与 `heads` 长度不同时 panic。
+pub fn serial_segmented_scan<T, F>(slice: &[T], heads: &[bool], op: F) -> Vec<T>
+where
+    T: Clone,
+    F: Fn(&T, &T) -> T,
+{
+    assert_eq!(
+        slice.len(),
+        heads.len(),
+        "segmented scan: values and heads have different lengths"
+    );
+    let mut out: Vec<T> = Vec::with_capacity(slice.len());
+    for (x, &head) in slice.iter().zip(heads) {
+        let value = match out.last() {
+            Some(prev) if !head => op(prev, x),
+            _ => x.clone(),
+        };
+        out.push(value);
+    }
+    out
+}
+
+/// 并行的分段包含式扫描：`heads[i]` 为 `true` 时从第 `i` 个元素开始一个新的段，
+/// 每段各自独立地做包含式扫描。
+///
+/// 把运算提升为 `(是否段首, 值)` 上的运算后，分段扫描同样满足结合律，从而可以复用
+/// [`par_inclusive_scan`]。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_segmented_scan;
+///
+/// let values = [1, 2, 3, 10, 20, 5];
+/// let heads = [true, false, false, true, false, true];
+/// assert_eq!(par_segmented_scan(&values, &heads, |a, b| a + b), [1, 3, 6, 10, 30, 5]);
+/// ```
+///
+/// # Panics
+///
+/// `slice` 与 `heads` 长度不同时 panic。
+pub fn par_segmented_scan<T, F>(slice: &[T], heads: &[bool], op: F) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T, &T) -> T + Sync,
+{
+    assert_eq!(
+        slice.len(),
+        heads.len(),
+        "segmented scan: values and heads have different lengths"
+    );
+    let flagged: Vec<(bool, T)> = heads
+        .par_iter()
+        .copied()
+        .zip(slice.par_iter().cloned())
+        .collect();
+    let scanned = par_inclusive_scan(&flagged, |(a_head, a), (b_head, b)| {
+        if *b_head {
+            (true, b.clone())
+        } else {
+            (*a_head, op(a, b))
+        }
+    });
+    scanned.into_par_iter().map(|(_, value)| value).collect()
+}
+
+/// 并行地保留满足条件的元素，保持原有顺序。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_filter;
+///
+/// let values: Vec<u32> = (0..20).collect();
+/// assert_eq!(par_filter(&values, |x| x % 7 == 0), [0, 7, 14]);
+/// ```
+pub fn par_filter<T, F>(slice: &[T], predicate: F) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+    F: Fn(&T) -> bool + Sync,
+{
+    slice.par_iter().filter(|x| predicate(x)).cloned().collect()
+}
+
+/// 流压缩：并行地保留 `keep[i]` 为 `true` 的元素，保持原有顺序。
+///
+/// # Panics
+///
+/// `slice` 与 `keep` 长度不同时 panic。
+pub fn par_compact<T>(slice: &[T], keep: &[bool]) -> Vec<T>
+where
+    T: Clone + Send + Sync,
+{
+    assert_eq!(
+        slice.len(),
+        keep.len(),
+        "compact: values and mask have different lengths"
+    );
+    slice
+        .par_iter()
+        .zip(keep)
+        .filter(|&(_, &keep)| keep)
+        .map(|(x, _)| x.clone())
+        .collect()
+}
+
+/// 并行地去掉相邻的重复元素，与 [`Vec::dedup`] 的结果相同；对有序输入即得到所有不同的值。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_dedup;
+///
+/// assert_eq!(par_dedup(&[1, 1, 2, 3, 3, 3, 1]), [1, 2, 3, 1]);
+/// ```
+pub fn par_dedup<T>(slice: &[T]) -> Vec<T>
+where
+    T: PartialEq + Clone + Send + Sync,
+{
+    slice
+        .par_iter()
+        .enumerate()
+        .filter(|&(i, x)| i == 0 || slice[i - 1] != *x)
+        .map(|(_, x)| x.clone())
+        .collect()
+}
+
+/// 串行地保留每个值第一次出现的位置，作为 [`par_unique`] 的对照。
+pub fn serial_unique<T: Ord + Clone>(slice: &[T]) -> Vec<T> {
+    let mut seen = std::collections::BTreeSet::new();
+    slice.iter().filter(|x| seen.insert(*x)).cloned().collect()
+}
+
+/// 并行地去除所有重复值，只保留每个值第一次出现的元素，保持原有顺序。
+///
+/// 先按 `(值, 下标)` 并行排序下标，标记每组相等值中下标最小的一个，
+/// 再把被标记的下标恢复为原有顺序。时间复杂度 O(n log n)。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_scan::par_unique;
+///
+/// assert_eq!(par_unique(&["b", "a", "b", "c", "a"]), ["b", "a", "c"]);
+/// ```
+pub fn par_unique<T>(slice: &[T]) -> Vec<T>
+where
+    T: Ord + Clone + Send + Sync,
+{
+    let mut order: Vec<usize> = (0..slice.len()).collect();
+    order.par_sort_unstable_by(|&a, &b| slice[a].cmp(&slice[b]).then(a.cmp(&b)));
+
+    let mut firsts: Vec<usize> = order
+        .par_iter()
+        .enumerate()
+        .filter(|&(k, &i)| k == 0 || slice[order[k - 1]] != slice[i])
+        .map(|(_, &i)| i)
+        .collect();
+    firsts.par_sort_unstable();
+    firsts.into_par_iter().map(|i| slice[i].clone()).collect()
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    /// 覆盖串行路径、恰好一个块、块边界两侧和多个块的长度
+    const LENGTHS: [usize; 8] = [
+        0,
+        1,
+        SCAN_BLOCK - 1,
+        SCAN_BLOCK,
+        SCAN_BLOCK + 1,
+        3 * SCAN_BLOCK,
+        5 * SCAN_BLOCK + 17,
+        100_000,
+    ];
+
+    /// 2×2 矩阵乘法，满足结合律但不满足交换律，用于检查扫描是否保持顺序
+    fn mat_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+        let m = |x: u64, y: u64| x.wrapping_mul(y);
+        [
+            m(a[0], b[0]).wrapping_add(m(a[1], b[2])),
+            m(a[0], b[1]).wrapping_add(m(a[1], b[3])),
+            m(a[2], b[0]).wrapping_add(m(a[3], b[2])),
+            m(a[2], b[1]).wrapping_add(m(a[3], b[3])),
+        ]
+    }
+
+    /// 测试包含式和排除式扫描与串行版本一致，包括不满足交换律的运算
+    #[test]
+    fn scans_match_serial() {
+        let mut rng = thread_rng();
+        for len in LENGTHS {
+            let values: Vec<i64> = (0..len).map(|_| rng.gen_range(-1_000..1_000)).collect();
+            let sum = |a: &i64, b: &i64| a + b;
+            assert_eq!(
+                par_inclusive_scan(&values, sum),
+                serial_inclusive_scan(&values, sum)
+            );
+            assert_eq!(
+                par_exclusive_scan(&values, 0, sum),
+                serial_exclusive_scan(&values, 0, sum)
+            );
+
+            let matrices: Vec<[u64; 4]> = (0..len).map(|_| rng.gen()).collect();
+            let expected = serial_inclusive_scan(&matrices, mat_mul);
+            assert_eq!(par_inclusive_scan(&matrices, mat_mul), expected);
+
+            let identity = [1, 0, 0, 1];
+            let exclusive = par_exclusive_scan(&matrices, identity, mat_mul);
+            assert_eq!(
+                exclusive,
+                serial_exclusive_scan(&matrices, identity, mat_mul)
+            );
+            if len > 0 {
+                assert_eq!(exclusive[0], identity);
+                assert_eq!(exclusive[1..], expected[..len - 1]);
+            }
+        }
+    }
+
+    /// 测试分段扫描与串行版本一致
+    #[test]
+    fn segmented_scan_matches_serial() {
+        let mut rng = thread_rng();
+        for len in LENGTHS {
+            let values: Vec<u64> = (0..len).map(|_| rng.gen_range(0..100)).collect();
+            for probability in [0.0, 0.001, 0.3, 1.0] {
+                let heads: Vec<bool> = (0..len).map(|_| rng.gen_bool(probability)).collect();
+                let sum = |a: &u64, b: &u64| a + b;
+                assert_eq!(
+                    par_segmented_scan(&values, &heads, sum),
+                    serial_segmented_scan(&values, &heads, sum)
+                );
+            }
+        }
+
+        // 串行参考实现本身的正确性
+        let heads = [false, false, true, false];
+        assert_eq!(
+            serial_segmented_scan(&["a", "b", "c", "d"].map(String::from), &heads, |a, b| a
+                .clone()
+                + b),
+            ["a", "ab", "c", "cd"]
+        );
+    }
+
+    /// 测试压缩、相邻去重和全局去重保持顺序
+    #[test]
+    fn compaction_and_unique() {
+        let mut rng = thread_rng();
+        for len in LENGTHS {
+            let values: Vec<u16> = (0..len).map(|_| rng.gen_range(0..50)).collect();
+
+            let expected: Vec<u16> = values.iter().copied().filter(|x| x % 3 == 0).collect();
+            assert_eq!(par_filter(&values, |x| x % 3 == 0), expected);
+            let mask: Vec<bool> = values.iter().map(|x| x % 3 == 0).collect();
+            assert_eq!(par_compact(&values, &mask), expected);
+
+            let mut deduped = values.clone();
+            deduped.dedup();
+            assert_eq!(par_dedup(&values), deduped);
+
+            assert_eq!(par_unique(&values), serial_unique(&values));
+        }
+        assert_eq!(serial_unique(&[3, 1, 3, 2, 1]), [3, 1, 2]);
+    }
+
+    /// 测试长度不一致时 panic
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn segmented_scan_length_mismatch() {
+        par_segmented_scan(&[1, 2, 3], &[true], |a, b| a + b);
+    }
+}
@@ -0,0 +1,702 @@
+//! 数值切片上的并行归约与统计量。
+//!
+//! 每个并行函数都有对应的 `serial_*` 参考实现，便于对照测试。浮点数的累加采用补偿求和或
+//! 两两求和，方差采用 Welford 算法，避免朴素累加在大规模数据上的精度损失。
+//! 除特别说明外，NaN 会参与求和与矩的计算（结果为 NaN），而在最小值、最大值、直方图和分位数中被单独处理。
+
+use rayon::prelude::*;
+
+/// 可以参与统计的数值类型，已为所有内置整数和浮点类型实现。
+pub trait Numeric: Copy + PartialOrd + Send + Sync {
+    /// 转换为 `f64`，超出 `f64` 精确表示范围的整数会被舍入。
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// 两两求和中不再继续二分的块大小。
+const PAIRWISE_BLOCK: usize = 128;
+
+/// 两两求和中不再并行二分的块大小，更小的块在当前线程中完成。
+const PAR_PAIRWISE_BLOCK: usize = 1 << 14;
+
+/// Kahan-Babuška（Neumaier）补偿求和的累加器。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KahanSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl KahanSum {
+    /// 累加一个值。
+    pub fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        // 记录被舍入丢掉的低位部分
+        if self.sum.abs() >= x.abs() {
+            self.compensation += (self.sum - t) + x;
+        } else {
+            self.compensation += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    /// 合并另一个累加器的结果。
+    pub fn merge(&mut self, other: KahanSum) {
+        self.add(other.sum);
+        self.compensation += other.compensation;
+    }
+
+    /// 当前的和。
+    pub fn total(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// 串行的补偿求和，作为 [`par_kahan_sum`] 的对照。
+pub fn serial_kahan_sum<T: Numeric>(slice: &[T]) -> f64 {
+    let mut acc = KahanSum::default();
+    slice.iter().for_each(|&x| acc.add(x.to_f64()));
+    acc.total()
+}
+
+/// 并行的补偿求和，误差与元素个数基本无关。
+///
+/// 各线程分别累加后再合并，不同的任务划分可能使结果在最后一位上略有不同；
+/// 需要结果与线程数无关时使用 [`par_pairwise_sum`]。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::par_kahan_sum;
+///
+/// let mut values = vec![1.0e16];
+/// values.extend(std::iter::repeat_n(1.0, 10_000));
+///
+/// // 朴素累加时每个 1.0 都会被舍入掉
+/// assert_eq!(values.iter().sum::<f64>(), 1.0e16);
+/// assert_eq!(par_kahan_sum(&values), 1.0e16 + 10_000.0);
+/// ```
+pub fn par_kahan_sum<T: Numeric>(slice: &[T]) -> f64 {
+    slice
+        .par_iter()
+        .fold(KahanSum::default, |mut acc, &x| {
+            acc.add(x.to_f64());
+            acc
+        })
+        .reduce(KahanSum::default, |mut a, b| {
+            a.merge(b);
+            a
+        })
+        .total()
+}
+
+/// 串行的两两求和，作为 [`par_pairwise_sum`] 的对照。
+pub fn serial_pairwise_sum<T: Numeric>(slice: &[T]) -> f64 {
+    if slice.len() <= PAIRWISE_BLOCK {
+        slice.iter().fold(0.0, |acc, &x| acc + x.to_f64())
+    } else {
+        let (left, right) = slice.split_at(slice.len() / 2);
+        serial_pairwise_sum(left) + serial_pairwise_sum(right)
+    }
+}
+
+/// 并行的两两求和，误差随元素个数按 O(log n) 增长。
+///
+/// 划分方式只取决于切片长度，因此结果与线程数和调度无关，并且与 [`serial_pairwise_sum`] 逐位相同。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::{par_pairwise_sum, serial_pairwise_sum};
+///
+/// let values: Vec<f32> = (0..100_000).map(|i| i as f32 * 0.1).collect();
+/// assert_eq!(par_pairwise_sum(&values), serial_pairwise_sum(&values));
+/// ```
+pub fn par_pairwise_sum<T: Numeric>(slice: &[T]) -> f64 {
+    if slice.len() <= PAR_PAIRWISE_BLOCK {
+        serial_pairwise_sum(slice)
+    } else {
+        let (left, right) = slice.split_at(slice.len() / 2);
+        let (a, b) = rayon::join(|| par_pairwise_sum(left), || par_pairwise_sum(right));
+        a + b
+    }
+}
+
+/// 串行的整数精确求和，作为 [`par_exact_sum`] 的对照。
+pub fn serial_exact_sum<T: Copy + Into<i128>>(slice: &[T]) -> i128 {
+    slice.iter().map(|&x| x.into()).sum()
+}
+
+/// 在 `i128` 中并行地对整数精确求和，64 位及更窄的整数不会溢出。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::par_exact_sum;
+///
+/// let values = vec![u64::MAX; 4];
+/// assert_eq!(par_exact_sum(&values), 4 * u64::MAX as i128);
+/// ```
+pub fn par_exact_sum<T: Copy + Into<i128> + Sync>(slice: &[T]) -> i128 {
+    slice.par_iter().map(|&x| x.into()).sum()
+}
+
+/// 串行地求最小值和最大值，作为 [`par_min_max`] 的对照。
+pub fn serial_min_max<T: Numeric>(slice: &[T]) -> Option<(T, T)> {
+    slice.iter().copied().fold(None, min_max_step)
+}
+
+/// 并行地求最小值和最大值，忽略 NaN。
+///
+/// # 返回值
+///
+/// 切片为空或只包含 NaN 时返回 `None`。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::par_min_max;
+///
+/// assert_eq!(par_min_max(&[3.5, f64::NAN, -1.0, 8.0]), Some((-1.0, 8.0)));
+/// assert_eq!(par_min_max::<i32>(&[]), None);
+/// ```
+pub fn par_min_max<T: Numeric>(slice: &[T]) -> Option<(T, T)> {
+    slice
+        .par_iter()
+        .copied()
+        .fold(|| None, min_max_step)
+        .reduce(
+            || None,
+            |a, b| match (a, b) {
+                (Some((a_min, a_max)), Some((b_min, b_max))) => Some((
+                    if b_min < a_min { b_min } else { a_min },
+                    if b_max > a_max { b_max } else { a_max },
+                )),
+                (a, None) => a,
+                (None, b) => b,
+            },
+        )
+}
+
+fn min_max_step<T: Numeric>(acc: Option<(T, T)>, x: T) -> Option<(T, T)> {
+    // NaN 与自身不可比较
+    if x.partial_cmp(&x).is_none() {
+        return acc;
+    }
+    match acc {
+        None => Some((x, x)),
+        Some((min, max)) => Some((if x < min { x } else { min }, if x > max { x } else { max })),
+    }
+}
+
+/// 用 Welford 算法在线计算的均值、方差和极值，可以合并两部分数据的结果。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Moments {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Moments {
+    /// 加入一个值。
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+        self.min = self.min.min(x);
+        self.max = self.max.max(x);
+    }
+
+    /// 合并另一部分数据的结果（Chan 等人的并行公式）。
+    pub fn merge(&mut self, other: Moments) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = other;
+            return;
+        }
+
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let (a, b) = (self.count as f64, other.count as f64);
+        self.mean += delta * b / count as f64;
+        self.m2 += other.m2 + delta * delta * a * b / count as f64;
+        self.count = count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// 值的个数。
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 均值，没有值时返回 `None`。
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    /// 总体方差（除以 n），没有值时返回 `None`。
+    pub fn variance(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.m2 / self.count as f64)
+    }
+
+    /// 样本方差（除以 n - 1），少于两个值时返回 `None`。
+    pub fn sample_variance(&self) -> Option<f64> {
+        (self.count > 1).then(|| self.m2 / (self.count - 1) as f64)
+    }
+
+    /// 总体标准差。
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// 最小值（忽略 NaN），没有值时返回 `None`。
+    pub fn min(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.min)
+    }
+
+    /// 最大值（忽略 NaN），没有值时返回 `None`。
+    pub fn max(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.max)
+    }
+}
+
+/// 串行地计算均值、方差和极值，作为 [`par_moments`] 的对照。
+pub fn serial_moments<T: Numeric>(slice: &[T]) -> Moments {
+    let mut moments = Moments::default();
+    slice.iter().for_each(|&x| moments.push(x.to_f64()));
+    moments
+}
+
+/// 并行地计算均值、方差和极值。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::par_moments;
+///
+/// // 很大的偏移量不会影响方差的精度
+/// let values: Vec<f64> = [4.0, 7.0, 13.0, 16.0].iter().map(|x| x + 1.0e9).collect();
+/// let moments = par_moments(&values);
+///
+/// assert_eq!(moments.count(), 4);
+/// assert_eq!(moments.mean(), Some(1.0e9 + 10.0));
+/// assert_eq!(moments.sample_variance(), Some(30.0));
+/// ```
+pub fn par_moments<T: Numeric>(slice: &[T]) -> Moments {
+    slice
+        .par_iter()
+        .fold(Moments::default, |mut acc, &x| {
+            acc.push(x.to_f64());
+            acc
+        })
+        .reduce(Moments::default, |mut a, b| {
+            a.merge(b);
+            a
+        })
+}
+
+/// 直方图的分箱方式。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins {
+    /// 把 `[low, high]` 等分为 `count` 个区间。
+    Uniform { low: f64, high: f64, count: usize },
+    /// 由严格递增的边界给出，`n` 个边界构成 `n - 1` 个区间。
+    Edges(Vec<f64>),
+}
+
+/// 直方图。除最后一个区间包含上边界外，每个区间都是左闭右开的。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// 区间边界，长度为区间数加一
+    pub edges: Vec<f64>,
+    /// 每个区间内的值的个数
+    pub counts: Vec<u64>,
+    /// 小于第一个边界的值的个数
+    pub below: u64,
+    /// 大于最后一个边界的值的个数
+    pub above: u64,
+    /// NaN 的个数
+    pub nan: u64,
+}
+
+impl Histogram {
+    fn empty(bins: &Bins) -> Self {
+        let edges = match *bins {
+            Bins::Uniform { low, high, count } => {
+                assert!(count > 0, "histogram: bin count must be positive");
+                assert!(low < high, "histogram: low must be less than high");
+                let width = (high - low) / count as f64;
+                (0..=count)
+                    .map(|i| {
+                        if i == count {
+                            high
+                        } else {
+                            low + width * i as f64
+                        }
+                    })
+                    .collect()
+            }
+            Bins::Edges(ref edges) => {
+                assert!(
+                    edges.len() >= 2,
+                    "histogram: at least two edges are required"
+                );
+                assert!(
+                    edges.windows(2).all(|w| w[0] < w[1]),
+                    "histogram: edges must be strictly increasing"
+                );
+                edges.clone()
+            }
+        };
+        Histogram {
+            counts: vec![0; edges.len() - 1],
+            edges,
+            below: 0,
+            above: 0,
+            nan: 0,
+        }
+    }
+
+    fn add(&mut self, x: f64) {
+        let last = self.edges.len() - 1;
+        if x.is_nan() {
+            self.nan += 1;
+        } else if x < self.edges[0] {
+            self.below += 1;
+        } else if x > self.edges[last] {
+            self.above += 1;
+        } else {
+            // 第一个大于 x 的边界之前的区间；等于上边界的值归入最后一个区间
+            let bin = self.edges.partition_point(|&e| e <= x).min(last) - 1;
+            self.counts[bin] += 1;
+        }
+    }
+
+    fn merge(mut self, other: Histogram) -> Self {
+        self.counts
+            .iter_mut()
+            .zip(&other.counts)
+            .for_each(|(a, b)| *a += b);
+        self.below += other.below;
+        self.above += other.above;
+        self.nan += other.nan;
+        self
+    }
+
+    /// 落在各区间内的值的总数。
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// 假设值在每个区间内均匀分布，估计区间内数据的 `q` 分位数。
+    ///
+    /// 区间外的值和 NaN 不参与估计。没有值落在区间内时返回 `None`。
+    ///
+    /// # Panics
+    ///
+    /// `q` 不在 `[0, 1]` 内时 panic。
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        assert!((0.0..=1.0).contains(&q), "quantile must be within [0, 1]");
+        let total = self.total();
+        if total == 0 {
+            return None;
+        }
+
+        let target = q * total as f64;
+        let mut seen = 0.0;
+        for (bin, &count) in self.counts.iter().enumerate() {
+            let next = seen + count as f64;
+            if count > 0 && next >= target {
+                let fraction = (target - seen) / count as f64;
+                let (low, high) = (self.edges[bin], self.edges[bin + 1]);
+                return Some(low + (high - low) * fraction);
+            }
+            seen = next;
+        }
+        self.edges.last().copied()
+    }
+}
+
+/// 串行地统计直方图，作为 [`par_histogram`] 的对照。
+///
+/// # Panics
+///
+/// 参见 [`par_histogram`]。
+pub fn serial_histogram<T: Numeric>(slice: &[T], bins: &Bins) -> Histogram {
+    let mut histogram = Histogram::empty(bins);
+    slice.iter().for_each(|&x| histogram.add(x.to_f64()));
+    histogram
+}
+
+/// 并行地统计直方图，每个线程先在自己的计数数组中累加，最后合并。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::{par_histogram, Bins};
+///
+/// let values = [0.5, 1.0, 2.5, 9.9, 10.0, -3.0, 42.0, f64::NAN];
+/// let histogram = par_histogram(&values, &Bins::Uniform { low: 0.0, high: 10.0, count: 5 });
+///
+/// assert_eq!(histogram.edges, [0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+/// assert_eq!(histogram.counts, [2, 1, 0, 0, 2]);
+/// assert_eq!((histogram.below, histogram.above, histogram.nan), (1, 1, 1));
+/// ```
+///
+/// # Panics
+///
+/// 均匀分箱的区间数为 0 或 `low >= high`，以及显式边界少于两个或不严格递增时 panic。
+pub fn par_histogram<T: Numeric>(slice: &[T], bins: &Bins) -> Histogram {
+    let empty = Histogram::empty(bins);
+    slice
+        .par_iter()
+        .fold(
+            || empty.clone(),
+            |mut histogram, &x| {
+                histogram.add(x.to_f64());
+                histogram
+            },
+        )
+        .reduce(|| empty.clone(), Histogram::merge)
+}
+
+/// 串行地计算精确分位数，作为 [`par_quantiles`] 的对照。
+///
+/// # Panics
+///
+/// 参见 [`par_quantiles`]。
+pub fn serial_quantiles<T: Numeric>(slice: &[T], qs: &[f64]) -> Option<Vec<f64>> {
+    let mut sorted: Vec<f64> = slice
+        .iter()
+        .map(|&x| x.to_f64())
+        .filter(|x| !x.is_nan())
+        .collect();
+    sorted.sort_unstable_by(f64::total_cmp);
+    interpolate_quantiles(&sorted, qs)
+}
+
+/// 并行排序后计算精确分位数，在相邻两个次序统计量之间线性插值，忽略 NaN。
+///
+/// # 参数
+///
+/// * `slice` - 数据。
+/// * `qs` - 要计算的分位点，每个都在 `[0, 1]` 内。
+///
+/// # 返回值
+///
+/// 与 `qs` 一一对应的分位数；没有非 NaN 的值时返回 `None`。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_stats::par_quantiles;
+///
+/// let values: Vec<u32> = (1..=101).rev().collect();
+/// assert_eq!(par_quantiles(&values, &[0.0, 0.5, 0.99]), Some(vec![1.0, 51.0, 100.0]));
+/// ```
+///
+/// # Panics
+///
+/// 某个分位点不在 `[0, 1]` 内时 panic。
+pub fn par_quantiles<T: Numeric>(slice: &[T], qs: &[f64]) -> Option<Vec<f64>> {
+    let mut sorted: Vec<f64> = slice
+        .par_iter()
+        .map(|&x| x.to_f64())
+        .filter(|x| !x.is_nan())
+        .collect();
+    sorted.par_sort_unstable_by(f64::total_cmp);
+    interpolate_quantiles(&sorted, qs)
+}
+
+fn interpolate_quantiles(sorted: &[f64], qs: &[f64]) -> Option<Vec<f64>> {
+    assert!(
+        qs.iter().all(|q| (0.0..=1.0).contains(q)),
+        "quantile must be within [0, 1]"
+    );
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let last = (sorted.len() - 1) as f64;
+    let quantiles = qs
+        .iter()
+        .map(|&q| {
+            let rank = q * last;
+            let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+            sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+        })
+        .collect();
+    Some(quantiles)
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+
+    fn random_floats(len: usize) -> Vec<f64> {
+        let mut rng = thread_rng();
+        (0..len).map(|_| rng.gen_range(-1.0e6..1.0e6)).collect()
+    }
+
+    /// 测试各种求和方式与参考实现一致，且补偿求和不丢失小量
+    #[test]
+    fn sums_match_serial() {
+        for len in [0, 1, 1_000, 100_000] {
+            let floats = random_floats(len);
+            let serial = serial_kahan_sum(&floats);
+            let tolerance = 1.0e-9 * floats.iter().map(|x| x.abs()).sum::<f64>().max(1.0);
+            assert!((par_kahan_sum(&floats) - serial).abs() <= tolerance);
+            assert!((par_pairwise_sum(&floats) - serial).abs() <= tolerance);
+            assert_eq!(par_pairwise_sum(&floats), serial_pairwise_sum(&floats));
+
+            let ints: Vec<i64> = floats.iter().map(|&x| x as i64 * 1_000_000).collect();
+            assert_eq!(par_exact_sum(&ints), serial_exact_sum(&ints));
+        }
+
+        let mut values = vec![1.0, 1.0e100, 1.0, -1.0e100];
+        values.extend(std::iter::repeat_n(0.1, 10));
+        assert_eq!(serial_kahan_sum(&values), 3.0);
+        assert_eq!(par_kahan_sum(&values), 3.0);
+    }
+
+    /// 测试极值忽略 NaN，整数和浮点数都与参考实现一致
+    #[test]
+    fn min_max_matches_serial() {
+        let mut floats = random_floats(50_000);
+        floats[123] = f64::NAN;
+        assert_eq!(par_min_max(&floats), serial_min_max(&floats));
+        assert_eq!(par_min_max(&[f32::NAN, f32::NAN]), None);
+
+        let ints: Vec<i16> = (-500..500).rev().collect();
+        assert_eq!(par_min_max(&ints), Some((-500, 499)));
+        assert_eq!(par_min_max(&ints), serial_min_max(&ints));
+    }
+
+    /// 测试合并后的矩与串行 Welford 及精确整数计算的结果一致
+    #[test]
+    fn moments_match_exact() {
+        let mut rng = thread_rng();
+        for len in [1, 2, 10_000] {
+            // 大偏移量加上整数扰动，参考值可以用整数精确计算
+            let offsets: Vec<i64> = (0..len)
+                .map(|_| rng.gen_range(-1_000_000..1_000_000))
+                .collect();
+            let values: Vec<f64> = offsets.iter().map(|&d| 1.0e12 + d as f64).collect();
+            let sum = serial_exact_sum(&offsets) as f64;
+            let squares: i128 = offsets.iter().map(|&d| d as i128 * d as i128).sum();
+            let mean = 1.0e12 + sum / len as f64;
+            let variance = squares as f64 / len as f64 - (sum / len as f64).powi(2);
+
+            let serial = serial_moments(&values);
+            let parallel = par_moments(&values);
+            assert_eq!(parallel.count(), len as u64);
+            for moments in [serial, parallel] {
+                assert!((moments.mean().unwrap() - mean).abs() <= 1.0e-13 * mean);
+                assert!(
+                    (moments.variance().unwrap() - variance).abs() <= 1.0e-6 * variance.max(1.0)
+                );
+            }
+            assert_eq!(parallel.min(), serial.min());
+            assert_eq!(parallel.max(), serial.max());
+        }
+
+        let empty = par_moments::<u8>(&[]);
+        assert_eq!(
+            (empty.mean(), empty.variance(), empty.min()),
+            (None, None, None)
+        );
+        assert_eq!(par_moments(&[5u8]).sample_variance(), None);
+    }
+
+    /// 测试直方图与参考实现一致，且所有值都被计数
+    #[test]
+    fn histogram_matches_serial() {
+        let mut values = random_floats(100_000);
+        values.push(f64::NAN);
+        let bin_specs = [
+            Bins::Uniform {
+                low: -5.0e5,
+                high: 5.0e5,
+                count: 37,
+            },
+            Bins::Edges(vec![-1.0e6, -10.0, 0.0, 10.0, 1.0e3, 1.0e6]),
+        ];
+
+        for bins in &bin_specs {
+            let histogram = par_histogram(&values, bins);
+            assert_eq!(histogram, serial_histogram(&values, bins));
+            assert_eq!(
+                histogram.total() + histogram.below + histogram.above + histogram.nan,
+                values.len() as u64
+            );
+        }
+
+        let ints: Vec<u8> = (0..=255).collect();
+        let histogram = par_histogram(&ints, &Bins::Edges(vec![0.0, 128.0, 255.0]));
+        assert_eq!(histogram.counts, [128, 128]);
+    }
+
+    /// 测试精确分位数与参考实现一致，直方图估计值接近精确值
+    #[test]
+    fn quantiles() {
+        let values = random_floats(200_000);
+        let qs = [0.0, 0.01, 0.25, 0.5, 0.75, 0.999, 1.0];
+        let exact = par_quantiles(&values, &qs).unwrap();
+        assert_eq!(Some(exact.clone()), serial_quantiles(&values, &qs));
+
+        let histogram = par_histogram(
+            &values,
+            &Bins::Uniform {
+                low: -1.0e6,
+                high: 1.0e6,
+                count: 1_000,
+            },
+        );
+        for (&q, &expected) in qs.iter().zip(&exact) {
+            let estimate = histogram.quantile(q).unwrap();
+            assert!((estimate - expected).abs() < 1.0e4, "q = {q}");
+        }
+
+        assert_eq!(par_quantiles(&[f64::NAN], &[0.5]), None);
+        assert_eq!(par_quantiles(&[1, 2, 3, 4], &[0.5]), Some(vec![2.5]));
+    }
+
+    /// 测试非法参数会 panic
+    #[test]
+    #[should_panic(expected = "strictly increasing")]
+    fn invalid_edges_panic() {
+        par_histogram(&[1.0], &Bins::Edges(vec![0.0, 2.0, 1.0]));
+    }
+}
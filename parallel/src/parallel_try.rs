@@ -0,0 +1,284 @@
+//! 可失败的并行遍历。
+//!
+//! 普通的并行函数（如 [`parallel_any_condition`](crate::parallel_check_conditions::parallel_any_condition)）
+//! 中只要有一个元素的闭包 panic，panic 就会传播到调用者，整个任务随之失败。本模块的函数接受返回
+//! `Result` 的闭包，并在每个元素上捕获 panic，把返回的错误和 panic 都转换为带有元素下标的 [`TryError`]。
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use rayon::prelude::*;
+
+/// 遇到错误时的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// 遇到第一个错误后尽快停止，只返回这一个错误
+    #[default]
+    FailFast,
+    /// 处理所有元素，按下标顺序返回全部错误
+    CollectAll,
+}
+
+/// 单个元素失败的原因。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure<E> {
+    /// 闭包返回了 `Err`
+    Error(E),
+    /// 闭包 panic，保存 panic 的消息
+    Panic(String),
+}
+
+/// 处理下标为 `index` 的元素时出现的错误。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TryError<E> {
+    /// 出错元素的下标
+    pub index: usize,
+    /// 失败的原因
+    pub failure: Failure<E>,
+}
+
+impl<E: fmt::Display> fmt::Display for TryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.failure {
+            Failure::Error(e) => write!(f, "element {} failed: {e}", self.index),
+            Failure::Panic(msg) => write!(f, "element {} panicked: {msg}", self.index),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for TryError<E> {}
+
+/// 在 `item` 上执行 `f`，把返回的错误和 panic 都转换为 [`TryError`]。
+fn run<T, U, E, F>(index: usize, item: &T, f: &F) -> Result<U, TryError<E>>
+where
+    F: Fn(&T) -> Result<U, E>,
+{
+    let failure = match panic::catch_unwind(AssertUnwindSafe(|| f(item))) {
+        Ok(Ok(value)) => return Ok(value),
+        Ok(Err(e)) => Failure::Error(e),
+        Err(payload) => Failure::Panic(panic_message(payload)),
+    };
+    Err(TryError { index, failure })
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "non-string panic payload".to_string(),
+        },
+    }
+}
+
+/// 并行地对每个元素执行可失败的 `f`。
+///
+/// # 参数
+///
+/// * `slice` - 一个引用，指向要处理的切片。
+/// * `mode` - 遇到错误时的处理方式，参见 [`FailureMode`]。
+/// * `f` - 处理单个元素的函数，可以返回错误，也可以 panic。
+///
+/// # 返回值
+///
+/// 所有元素都成功时返回 `Ok(())`，否则返回按下标排序的错误列表。
+/// [`FailureMode::FailFast`] 模式下列表中只有一个错误，但它不一定是下标最小的失败元素，
+/// 尚未开始处理的元素会被跳过。
+///
+/// 被捕获的 panic 仍会经过当前的 panic hook（默认会打印到标准错误）。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_try::{try_par_for_each, Failure, FailureMode};
+///
+/// let v = vec![1, 2, 0, 4, 0];
+/// let errors = try_par_for_each(&v, FailureMode::CollectAll, |&x| {
+///     if x == 0 {
+///         Err("zero")
+///     } else {
+///         Ok(())
+///     }
+/// })
+/// .unwrap_err();
+///
+/// assert_eq!(errors.iter().map(|e| e.index).collect::<Vec<_>>(), [2, 4]);
+/// assert_eq!(errors[0].failure, Failure::Error("zero"));
+/// ```
+pub fn try_par_for_each<T, E, F>(
+    slice: &[T],
+    mode: FailureMode,
+    f: F,
+) -> Result<(), Vec<TryError<E>>>
+where
+    T: Sync,
+    E: Send,
+    F: Fn(&T) -> Result<(), E> + Sync + Send,
+{
+    let items = slice.par_iter().enumerate();
+    match mode {
+        FailureMode::FailFast => items
+            .try_for_each(|(i, item)| run(i, item, &f))
+            .map_err(|e| vec![e]),
+        FailureMode::CollectAll => {
+            let errors: Vec<TryError<E>> = items
+                .filter_map(|(i, item)| run(i, item, &f).err())
+                .collect();
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(errors)
+            }
+        }
+    }
+}
+
+/// 并行地把可失败的 `f` 应用到每个元素，按原有顺序返回结果。
+///
+/// # 参数
+///
+/// * `slice` - 一个引用，指向要处理的切片。
+/// * `mode` - 遇到错误时的处理方式，参见 [`FailureMode`]。
+/// * `f` - 把单个元素转换为结果的函数，可以返回错误，也可以 panic。
+///
+/// # 返回值
+///
+/// 所有元素都成功时返回与输入等长的结果向量，否则与 [`try_par_for_each`] 相同。
+///
+/// # 示例
+///
+/// ```
+/// use parallel::parallel_try::{try_par_map, Failure, FailureMode};
+///
+/// let v = vec!["1", "2", "x"];
+/// assert!(try_par_map(&v, FailureMode::FailFast, |s| s.parse::<i32>()).is_err());
+///
+/// let errors = try_par_map(&v, FailureMode::FailFast, |s| -> Result<i32, String> {
+///     Ok(s.parse().expect("not a number"))
+/// })
+/// .unwrap_err();
+/// assert_eq!(errors[0].index, 2);
+/// assert!(matches!(&errors[0].failure, Failure::Panic(msg) if msg.contains("not a number")));
+///
+/// let ok = try_par_map(&v[..2], FailureMode::CollectAll, |s| s.parse::<i32>());
+/// assert_eq!(ok, Ok(vec![1, 2]));
+/// ```
+pub fn try_par_map<T, U, E, F>(
+    slice: &[T],
+    mode: FailureMode,
+    f: F,
+) -> Result<Vec<U>, Vec<TryError<E>>>
+where
+    T: Sync,
+    U: Send,
+    E: Send,
+    F: Fn(&T) -> Result<U, E> + Sync + Send,
+{
+    let items = slice.par_iter().enumerate();
+    match mode {
+        FailureMode::FailFast => items
+            .map(|(i, item)| run(i, item, &f))
+            .collect::<Result<Vec<U>, _>>()
+            .map_err(|e| vec![e]),
+        FailureMode::CollectAll => {
+            let results: Vec<Result<U, TryError<E>>> =
+                items.map(|(i, item)| run(i, item, &f)).collect();
+            if results.iter().all(Result::is_ok) {
+                Ok(results.into_iter().flatten().collect())
+            } else {
+                Err(results.into_iter().filter_map(Result::err).collect())
+            }
+        }
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn check(x: &usize) -> Result<usize, String> {
+        match x % 100 {
+            13 => Err(format!("bad {x}")),
+            57 => panic!("boom at {x}"),
+            _ => Ok(x * 2),
+        }
+    }
+
+    /// 测试全部成功时返回与串行相同的结果
+    #[test]
+    fn all_ok() {
+        let v: Vec<usize> = (0..10_000).map(|x| x * 100).collect();
+        for mode in [FailureMode::FailFast, FailureMode::CollectAll] {
+            let expected: Vec<usize> = v.iter().map(|x| x * 2).collect();
+            assert_eq!(try_par_map(&v, mode, check), Ok(expected));
+            assert_eq!(try_par_for_each(&v, mode, |x| check(x).map(drop)), Ok(()));
+        }
+    }
+
+    /// 测试收集全部错误，包括 panic，并按下标排序
+    #[test]
+    fn collect_all_reports_every_failure() {
+        let v: Vec<usize> = (0..1_000).collect();
+        let errors = try_par_map(&v, FailureMode::CollectAll, check).unwrap_err();
+
+        let expected: Vec<usize> = v
+            .iter()
+            .copied()
+            .filter(|x| matches!(x % 100, 13 | 57))
+            .collect();
+        assert_eq!(errors.iter().map(|e| e.index).collect::<Vec<_>>(), expected);
+        for e in &errors {
+            match &e.failure {
+                Failure::Error(msg) => assert_eq!(*msg, format!("bad {}", e.index)),
+                Failure::Panic(msg) => assert_eq!(*msg, format!("boom at {}", e.index)),
+            }
+        }
+        assert_eq!(errors[1].to_string(), "element 57 panicked: boom at 57");
+
+        let for_each = try_par_for_each(&v, FailureMode::CollectAll, |x| check(x).map(drop));
+        assert_eq!(for_each.unwrap_err(), errors);
+    }
+
+    /// 测试快速失败只返回一个错误，并跳过剩余的元素
+    #[test]
+    fn fail_fast_stops_early() {
+        let v: Vec<usize> = (0..1_000_000).collect();
+        let visited = AtomicUsize::new(0);
+        let errors = try_par_for_each(&v, FailureMode::FailFast, |&x| {
+            visited.fetch_add(1, Ordering::Relaxed);
+            if x == 10 {
+                Err(x)
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            errors,
+            [TryError {
+                index: 10,
+                failure: Failure::Error(10)
+            }]
+        );
+        assert!(visited.load(Ordering::Relaxed) < v.len());
+    }
+
+    /// 测试非字符串的 panic 负载
+    #[test]
+    fn non_string_panic() {
+        let errors = try_par_for_each(&[()], FailureMode::FailFast, |_| -> Result<(), ()> {
+            panic::panic_any(42)
+        })
+        .unwrap_err();
+        assert_eq!(
+            errors[0].failure,
+            Failure::Panic("non-string panic payload".into())
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//! 对比串行与并行排序的性能。
+//!
+//! 运行方式：`cargo bench -p vector_sort --bench par_sort`
+//!
+//! 默认测试 10^3 到 10^7 个元素，可以通过环境变量 `SORT_BENCH_MAX_EXP=8` 把规模扩大到 10^8，
+//! 注意 10^8 个 `u64` 需要约 800MB 内存（排序时还需要额外的副本）。
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vector_sort::comparator::DefaultOrder;
+use vector_sort::par_sort::{par_sample_sort_by, par_sort_by, par_sort_unstable_by};
+
+/// 对同一份输入重复运行 `f`，返回耗时的中位数
+fn measure<F>(input: &[u64], runs: usize, mut f: F) -> Duration
+where
+    F: FnMut(&mut Vec<u64>),
+{
+    let mut times: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let mut data = input.to_vec();
+            let start = Instant::now();
+            f(&mut data);
+            let elapsed = start.elapsed();
+            assert!(data.windows(2).all(|w| w[0] <= w[1]));
+            elapsed
+        })
+        .collect();
+    times.sort();
+    times[runs / 2]
+}
+
+fn main() {
+    let max_exp: u32 = std::env::var("SORT_BENCH_MAX_EXP")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(7);
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    println!(
+        "{:>12} {:>12} {:>14} {:>12} {:>16} {:>12}",
+        "len", "sort", "sort_unstable", "par_sort", "par_sort_unstbl", "par_sample"
+    );
+
+    for exp in 3..=max_exp {
+        let len = 10usize.pow(exp);
+        let input: Vec<u64> = (0..len).map(|_| rng.gen()).collect();
+        // 规模越大重复次数越少，避免运行时间过长
+        let runs = if exp <= 5 { 11 } else { 3 };
+
+        let sort = measure(&input, runs, |v| v.sort());
+        let sort_unstable = measure(&input, runs, |v| v.sort_unstable());
+        let par = measure(&input, runs, |v| par_sort_by(v, DefaultOrder));
+        let par_unstable = measure(&input, runs, |v| par_sort_unstable_by(v, DefaultOrder));
+        let par_sample = measure(&input, runs, |v| par_sample_sort_by(v, DefaultOrder));
+
+        println!(
+            "{:>12} {:>12.3?} {:>14.3?} {:>12.3?} {:>16.3?} {:>12.3?}",
+            len, sort, sort_unstable, par, par_unstable, par_sample
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! 对比完整排序与 top-k / 部分排序 / 选择算法的性能，用于验证文档中的复杂度。
+//!
+//! 运行方式：`cargo bench -p vector_sort --bench selection`
+//!
+//! - 完整排序：O(n log n)
+//! - `partial_sort`：平均 O(n + k log k)
+//! - `top_k`：O(n log k)，额外空间 O(k)
+//! - `select_nth`：平均 O(n)
+//!
+//! 当 k 固定时，后三者的耗时应随 n 近似线性增长，而完整排序增长得更快。
+use std::time::{Duration, Instant};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use vector_sort::comparator::DefaultOrder;
+use vector_sort::select::{partial_sort, select_nth, top_k};
+
+const K: usize = 100;
+
+/// 对同一份输入重复运行 `f`，返回耗时的中位数
+fn measure<F>(input: &[u64], runs: usize, mut f: F) -> Duration
+where
+    F: FnMut(&mut Vec<u64>),
+{
+    let mut times: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let mut data = input.to_vec();
+            let start = Instant::now();
+            f(&mut data);
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[runs / 2]
+}
+
+fn main() {
+    let max_exp: u32 = std::env::var("SELECT_BENCH_MAX_EXP")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(7);
+
+    let mut rng = StdRng::seed_from_u64(42);
+
+    println!("k = {K}");
+    println!(
+        "{:>10} {:>12} {:>14} {:>12} {:>12} {:>12}",
+        "len", "sort", "sort_unstable", "partial", "top_k", "select_nth"
+    );
+
+    for exp in 3..=max_exp {
+        let len = 10usize.pow(exp);
+        let input: Vec<u64> = (0..len).map(|_| rng.gen()).collect();
+        let runs = if exp <= 5 { 11 } else { 3 };
+
+        let sort = measure(&input, runs, |v| v.sort());
+        let sort_unstable = measure(&input, runs, |v| v.sort_unstable());
+        let partial = measure(&input, runs, |v| partial_sort(v, K, DefaultOrder));
+        let top = measure(&input, runs, |v| {
+            std::hint::black_box(top_k(v, K, DefaultOrder));
+        });
+        let nth = measure(&input, runs, |v| {
+            std::hint::black_box(select_nth(v, K, DefaultOrder));
+        });
+
+        println!(
+            "{:>10} {:>12.3?} {:>14.3?} {:>12.3?} {:>12.3?} {:>12.3?}",
+            len, sort, sort_unstable, partial, top, nth
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Unicode 排序规则（collation）与自然排序。
+//!
+//! `String` 的 `Ord` 按 UTF-8 字节比较，因此 `"Émile"` 会排在 `"zoe"` 之后，`"file10"` 会排在
+//! `"file2"` 之前。这里实现一个简化的多级排序规则（参考 Unicode Collation Algorithm）：
+//!
+//! 1. **第一级**：只比较基本字母，忽略重音和大小写（`"é" == "E"`）；
+//! 2. **第二级**：第一级相同时比较重音（`"e" < "é"`）；
+//! 3. **第三级**：前两级相同时比较大小写，小写优先（`"e" < "E"`）。
+//!
+//! 自然排序在此基础上把连续的数字当作一个整数比较（`"file2" < "file10"`）。
+//!
+//! 这里没有实现特定语言的规则（如瑞典语把 `ä` 排在 `z` 之后），
+//! 所有字母都按去掉重音后的 Unicode 码位排序，对拉丁字母来说即字母表顺序。
+
+use std::cmp::Ordering;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// 排序规则的比较强度，强度越高区分得越细。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strength {
+    /// 只区分基本字母：忽略重音和大小写。
+    Primary,
+    /// 区分基本字母和重音，忽略大小写。
+    Secondary,
+    /// 区分基本字母、重音和大小写。
+    Tertiary,
+}
+
+/// 基本字符：数字串（仅自然排序）排在字母之前。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Base {
+    /// 去掉前导零后的数字串，先比较长度再逐位比较，即按数值比较
+    Number { len: usize, digits: String },
+    /// 小写形式的基本字符
+    Char(char),
+}
+
+#[derive(Debug, Clone)]
+struct Unit {
+    base: Base,
+    /// 附加在基本字符上的组合重音符号
+    accents: Vec<char>,
+    /// 第三级的区分信息：是否为大写；对数字串则为前导零个数
+    tertiary: (bool, usize),
+}
+
+/// 预先计算的排序键，实现了 `Ord`，适合配合 `sort_by_cached_key` 使用以避免重复计算。
+///
+/// 两个键以不同强度构建时，按其中较低的强度比较，因此 `a.cmp(&b)` 与 `b.cmp(&a)` 总是相反。
+/// 混用强度会让相等关系失去传递性，同一次排序中的键仍应以相同的强度构建。
+#[derive(Debug, Clone)]
+pub struct CollationKey {
+    units: Vec<Unit>,
+    strength: Strength,
+}
+
+impl CollationKey {
+    fn new(s: &str, strength: Strength, natural: bool) -> Self {
+        let mut units: Vec<Unit> = Vec::with_capacity(s.len());
+        let mut chars = s.nfd().flat_map(expand).peekable();
+
+        while let Some(c) = chars.next() {
+            if natural && c.is_ascii_digit() {
+                let mut digits = String::from(c);
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                let trimmed = digits.trim_start_matches('0');
+                units.push(Unit {
+                    base: Base::Number {
+                        len: trimmed.len(),
+                        digits: trimmed.to_string(),
+                    },
+                    accents: Vec::new(),
+                    tertiary: (false, digits.len() - trimmed.len()),
+                });
+            } else if is_combining_mark(c) && !units.is_empty() {
+                units.last_mut().unwrap().accents.push(c);
+            } else {
+                let upper = c.is_uppercase();
+                units.extend(c.to_lowercase().map(|lower| Unit {
+                    base: Base::Char(lower),
+                    accents: Vec::new(),
+                    tertiary: (upper, 0),
+                }));
+            }
+        }
+
+        CollationKey { units, strength }
+    }
+}
+
+impl Ord for CollationKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b) = (&self.units, &other.units);
+        let strength = self.strength.min(other.strength);
+
+        let primary = a.iter().map(|u| &u.base).cmp(b.iter().map(|u| &u.base));
+        if primary.is_ne() || strength == Strength::Primary {
+            return primary;
+        }
+
+        let secondary = a
+            .iter()
+            .map(|u| &u.accents)
+            .cmp(b.iter().map(|u| &u.accents));
+        if secondary.is_ne() || strength == Strength::Secondary {
+            return secondary;
+        }
+
+        a.iter()
+            .map(|u| u.tertiary)
+            .cmp(b.iter().map(|u| u.tertiary))
+    }
+}
+
+impl PartialOrd for CollationKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for CollationKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for CollationKey {}
+
+/// 规范分解无法拆开的常见字母，展开为基本字母（必要时加上用于第二级区分的组合符号）。
+fn expand(c: char) -> impl Iterator<Item = char> {
+    let expanded: &[char] = match c {
+        'ß' => &['s', 's'],
+        'ẞ' => &['S', 'S'],
+        'æ' => &['a', 'e'],
+        'Æ' => &['A', 'E'],
+        'œ' => &['o', 'e'],
+        'Œ' => &['O', 'E'],
+        'þ' => &['t', 'h'],
+        'Þ' => &['T', 'H'],
+        'ø' => &['o', '\u{0338}'],
+        'Ø' => &['O', '\u{0338}'],
+        'đ' => &['d', '\u{0335}'],
+        'Đ' => &['D', '\u{0335}'],
+        'ł' => &['l', '\u{0337}'],
+        'Ł' => &['L', '\u{0337}'],
+        _ => &[],
+    };
+
+    let single = expanded.is_empty().then_some(c);
+    expanded.iter().copied().chain(single)
+}
+
+/// 为字符串构建排序键。
+pub fn collation_key(s: &str, strength: Strength) -> CollationKey {
+    CollationKey::new(s, strength, false)
+}
+
+/// 为字符串构建自然排序键，连续的 ASCII 数字按整数比较。
+pub fn natural_key(s: &str, strength: Strength) -> CollationKey {
+    CollationKey::new(s, strength, true)
+}
+
+/// 按排序规则比较两个字符串。
+///
+/// 每次调用都会重新构建两个 [`CollationKey`]，适合偶尔的比较。用于排序时，
+/// `sort_by(|a, b| collate(a, b, ..))` 会构建 O(n log n) 个键，
+/// 应改用 `sort_by_cached_key`，每个元素只构建一次：
+///
+/// ```
+/// use vector_sort::collation::{collation_key, Strength};
+///
+/// let mut names = vec!["zoe", "Émile", "emile"];
+/// names.sort_by_cached_key(|s| collation_key(s, Strength::Tertiary));
+/// assert_eq!(names, ["emile", "Émile", "zoe"]);
+/// ```
+///
+/// # 示例
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use vector_sort::collation::{collate, Strength};
+///
+/// assert_eq!(collate("Émile", "zoe", Strength::Tertiary), Ordering::Less);
+/// assert_eq!(collate("résumé", "Resume", Strength::Primary), Ordering::Equal);
+/// assert_eq!(collate("resume", "résumé", Strength::Secondary), Ordering::Less);
+/// assert_eq!(collate("resume", "Resume", Strength::Tertiary), Ordering::Less);
+/// ```
+pub fn collate(a: &str, b: &str, strength: Strength) -> Ordering {
+    collation_key(a, strength).cmp(&collation_key(b, strength))
+}
+
+/// 自然排序比较：数字串按数值比较，其余部分按排序规则比较。
+///
+/// 与 [`collate`] 相同，每次调用都会构建两个键；对大量字符串排序时应使用
+/// `sort_by_cached_key` 配合 [`natural_key`]。
+///
+/// # 示例
+///
+/// ```
+/// use std::cmp::Ordering;
+/// use vector_sort::collation::{natural_cmp, Strength};
+///
+/// let mut files = vec!["file10.txt", "File2.txt", "file1.txt"];
+/// files.sort_by(|a, b| natural_cmp(a, b, Strength::Tertiary));
+///
+/// assert_eq!(files, ["file1.txt", "File2.txt", "file10.txt"]);
+/// assert_eq!(natural_cmp("v007", "v7", Strength::Secondary), Ordering::Equal);
+/// ```
+pub fn natural_cmp(a: &str, b: &str, strength: Strength) -> Ordering {
+    natural_key(a, strength).cmp(&natural_key(b, strength))
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(words: &[&str], cmp: impl Fn(&str, &str) -> Ordering) -> Vec<String> {
+        let mut vec: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        vec.sort_by(|a, b| cmp(a, b));
+        vec
+    }
+
+    /// 测试重音和大小写不再影响基本字母的顺序
+    #[test]
+    fn accents_and_case_follow_base_letters() {
+        let words = ["zoe", "Émile", "emile", "Zoë", "Eve", "émile"];
+        assert_eq!(
+            sorted(&words, |a, b| collate(a, b, Strength::Tertiary)),
+            ["emile", "émile", "Émile", "Eve", "zoe", "Zoë"]
+        );
+
+        // 字节序下 "Émile" 排在最后
+        let mut bytes = words.to_vec();
+        bytes.sort();
+        assert_eq!(bytes.last(), Some(&"émile"));
+    }
+
+    /// 测试不同强度下的相等性
+    #[test]
+    fn strength_levels() {
+        assert!(collate("Résumé", "resume", Strength::Primary).is_eq());
+        assert!(collate("Résumé", "résumé", Strength::Secondary).is_eq());
+        assert!(collate("Résumé", "résumé", Strength::Tertiary).is_gt());
+        // 第一级的差异优先于后面任何位置的重音差异
+        assert!(collate("éa", "eb", Strength::Tertiary).is_lt());
+        // 预组合字符与分解形式等价
+        assert!(collate("e\u{301}", "\u{e9}", Strength::Tertiary).is_eq());
+    }
+
+    /// 测试不同强度的键按较低的强度比较，两个方向的结果一致
+    #[test]
+    fn mixed_strengths_are_antisymmetric() {
+        let strengths = [Strength::Primary, Strength::Secondary, Strength::Tertiary];
+        let words = ["resume", "Résumé", "résumé", "Resume", "rèsume"];
+        for &x in &words {
+            for &y in &words {
+                for &sx in &strengths {
+                    for &sy in &strengths {
+                        let (a, b) = (collation_key(x, sx), collation_key(y, sy));
+                        assert_eq!(a.cmp(&b), b.cmp(&a).reverse(), "{x} {sx:?} vs {y} {sy:?}");
+                        assert_eq!(a.cmp(&b), collate(x, y, sx.min(sy)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 测试无法分解的特殊字母
+    #[test]
+    fn expansions() {
+        assert!(collate("Straße", "strasse", Strength::Secondary).is_eq());
+        assert!(collate("Ærø", "aero", Strength::Primary).is_eq());
+        assert!(collate("aero", "ærø", Strength::Secondary).is_lt());
+        assert!(collate("Łódź", "lodz", Strength::Primary).is_eq());
+    }
+
+    /// 测试自然排序
+    #[test]
+    fn natural_ordering() {
+        let files = [
+            "file10", "file2", "file1", "File01", "file", "2file", "file1a",
+        ];
+        assert_eq!(
+            sorted(&files, |a, b| natural_cmp(a, b, Strength::Tertiary)),
+            ["2file", "file", "file1", "File01", "file1a", "file2", "file10"]
+        );
+
+        // 超出 u64 范围的数字串也能正确比较
+        assert!(natural_cmp(
+            "id99999999999999999999",
+            "id100000000000000000000",
+            Strength::Primary
+        )
+        .is_lt());
+        // 不是自然排序时数字按字符比较
+        assert!(collate("file10", "file2", Strength::Primary).is_lt());
+    }
+
+    /// 测试排序键可以缓存后使用
+    #[test]
+    fn cached_keys() {
+        let mut names = vec!["Zoë", "adam", "Émile"];
+        names.sort_by_cached_key(|n| collation_key(n, Strength::Tertiary));
+        assert_eq!(names, ["adam", "Émile", "Zoë"]);
+    }
+}
@@ -0,0 +1,503 @@
+use std::cmp::Ordering;
+
+use crate::collation::{self, Strength};
+
+/// 比较器抽象：所有接受比较规则的排序接口都以该 trait 为参数。
+///
+/// 任何 `Fn(&T, &T) -> Ordering` 闭包（即 `sort_by` 接受的形式）、
+/// 声明式构建的 [`Comparator`] 以及按 `Ord` 比较的 [`DefaultOrder`] 都实现了它。
+pub trait Compare<T: ?Sized> {
+    /// 比较两个元素。
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+impl<T: ?Sized, F> Compare<T> for F
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// 按照元素自身的 `Ord` 实现进行比较，相当于 `sort` 的默认行为。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultOrder;
+
+impl<T: Ord + ?Sized> Compare<T> for DefaultOrder {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// 字符串键的比较方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrOrder {
+    /// 按字节序比较，与 `String` 的 `Ord` 相同。
+    #[default]
+    Ordinal,
+    /// 忽略大小写比较（基于 Unicode 小写映射）。
+    CaseInsensitive,
+    /// 字典序：先忽略大小写比较，相同时小写排在大写之前，
+    /// 即 `"apple" < "Apple" < "banana"`，接近常见语言环境下的排序习惯。
+    Dictionary,
+    /// Unicode 排序规则：忽略重音和大小写比较基本字母，再按强度依次区分重音和大小写，
+    /// 即 `"emile" < "Émile" < "zoe"`。参见 [`collation`](crate::collation)。
+    ///
+    /// 每次比较都要为两个字符串各构建一个排序键（规范分解并分配内存），
+    /// 用于 [`Comparator::sort`] 时共需 O(n log n) 次构建。字符串较多时，应改用
+    /// `sort_by_cached_key` 配合 [`collation_key`](crate::collation::collation_key)，
+    /// 每个元素只构建一次键。
+    Collation(Strength),
+    /// 自然排序：在排序规则的基础上把连续数字按数值比较，即 `"file2" < "file10"`。
+    ///
+    /// 与 [`StrOrder::Collation`] 一样每次比较都构建排序键，大量排序时应改用
+    /// `sort_by_cached_key` 配合 [`natural_key`](crate::collation::natural_key)。
+    Natural(Strength),
+}
+
+impl StrOrder {
+    /// 按照当前方式比较两个字符串。
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            StrOrder::Ordinal => a.cmp(b),
+            StrOrder::CaseInsensitive => cmp_case_insensitive(a, b),
+            StrOrder::Dictionary => cmp_case_insensitive(a, b).then_with(|| {
+                // 忽略大小写后相同，则逐字符比较，小写优先
+                a.chars()
+                    .zip(b.chars())
+                    .map(|(x, y)| x.is_uppercase().cmp(&y.is_uppercase()))
+                    .find(|o| o.is_ne())
+                    .unwrap_or_else(|| a.cmp(b))
+            }),
+            StrOrder::Collation(strength) => collation::collate(a, b, strength),
+            StrOrder::Natural(strength) => collation::natural_cmp(a, b, strength),
+        }
+    }
+}
+
+/// 不分配内存地逐字符比较两个字符串的小写形式。
+fn cmp_case_insensitive(a: &str, b: &str) -> Ordering {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .cmp(b.chars().flat_map(char::to_lowercase))
+}
+
+/// 可选键中 `None` 的放置方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nulls {
+    /// `None` 排在所有 `Some` 之前。
+    First,
+    /// `None` 排在所有 `Some` 之后。
+    Last,
+}
+
+impl Nulls {
+    /// 比较两个可选值，`Some` 之间的顺序由 `cmp` 决定。
+    ///
+    /// `None` 的位置不受排序方向影响，降序时依然按 `self` 放置。
+    pub fn compare<K, F>(self, a: Option<K>, b: Option<K>, cmp: F) -> Ordering
+    where
+        F: FnOnce(&K, &K) -> Ordering,
+    {
+        match (a, b) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => match self {
+                Nulls::First => Ordering::Less,
+                Nulls::Last => Ordering::Greater,
+            },
+            (Some(_), None) => match self {
+                Nulls::First => Ordering::Greater,
+                Nulls::Last => Ordering::Less,
+            },
+            (Some(a), Some(b)) => cmp(&a, &b),
+        }
+    }
+}
+
+type KeyCmp<'a, T> = Box<dyn Fn(&T, &T) -> Ordering + Send + Sync + 'a>;
+
+/// 声明式的多键比较器。
+///
+/// 通过链式调用依次添加排序键，每个键可以单独指定方向、空值处理和字符串比较方式。
+/// 比较时按添加顺序逐个比较，前一个键相等时才比较下一个键。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::comparator::{by_ref, by_str, StrOrder};
+///
+/// struct Person {
+///     name: String,
+///     age: u32,
+/// }
+///
+/// let mut people = vec![
+///     Person { name: "al".to_string(), age: 20 },
+///     Person { name: "Al".to_string(), age: 60 },
+///     Person { name: "Zoe".to_string(), age: 25 },
+/// ];
+///
+/// // 先按名字升序，名字相同再按年龄降序
+/// let cmp = by_ref(|p: &Person| &p.name).then_desc(|p| p.age);
+/// cmp.sort(&mut people);
+/// assert_eq!(people[0].name, "Al");
+///
+/// // 名字忽略大小写比较，"Al" 与 "al" 视为相同，于是按年龄降序
+/// let cmp = by_str(|p: &Person| &p.name, StrOrder::CaseInsensitive).then_desc(|p| p.age);
+/// cmp.sort(&mut people);
+/// assert_eq!(people[0].age, 60);
+/// assert_eq!(people[1].age, 20);
+/// ```
+pub struct Comparator<'a, T> {
+    keys: Vec<KeyCmp<'a, T>>,
+}
+
+impl<'a, T: 'a> Default for Comparator<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: 'a> Comparator<'a, T> {
+    /// 创建一个没有任何排序键的比较器，此时所有元素都视为相等。
+    pub fn new() -> Self {
+        Comparator { keys: Vec::new() }
+    }
+
+    /// 添加一个升序的键，键由 `f` 按值提取（适合 `Copy` 或需要计算的键）。
+    pub fn then<K, F>(self, f: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| f(a).cmp(&f(b)))
+    }
+
+    /// 添加一个降序的键，键由 `f` 按值提取。
+    pub fn then_desc<K, F>(self, f: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| f(b).cmp(&f(a)))
+    }
+
+    /// 添加一个升序的键，键由 `f` 以引用形式借出，避免克隆 `String` 等字段。
+    pub fn then_ref<K, F>(self, f: F) -> Self
+    where
+        K: Ord + ?Sized,
+        F: Fn(&T) -> &K + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| f(a).cmp(f(b)))
+    }
+
+    /// 添加一个降序的借用键。
+    pub fn then_ref_desc<K, F>(self, f: F) -> Self
+    where
+        K: Ord + ?Sized,
+        F: Fn(&T) -> &K + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| f(b).cmp(f(a)))
+    }
+
+    /// 添加一个升序的字符串键，并指定字符串比较方式。
+    pub fn then_str<F>(self, f: F, order: StrOrder) -> Self
+    where
+        F: Fn(&T) -> &str + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| order.compare(f(a), f(b)))
+    }
+
+    /// 添加一个降序的字符串键，并指定字符串比较方式。
+    pub fn then_str_desc<F>(self, f: F, order: StrOrder) -> Self
+    where
+        F: Fn(&T) -> &str + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| order.compare(f(b), f(a)))
+    }
+
+    /// 添加一个升序的可选键，`None` 按 `nulls` 放置。
+    pub fn then_option<K, F>(self, f: F, nulls: Nulls) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> Option<K> + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| nulls.compare(f(a), f(b), |x, y| x.cmp(y)))
+    }
+
+    /// 添加一个降序的可选键，`None` 依然按 `nulls` 放置。
+    pub fn then_option_desc<K, F>(self, f: F, nulls: Nulls) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> Option<K> + Send + Sync + 'a,
+    {
+        self.then_with(move |a, b| nulls.compare(f(a), f(b), |x, y| y.cmp(x)))
+    }
+
+    /// 添加一个自定义的比较函数作为下一个键。
+    pub fn then_with<F>(mut self, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'a,
+    {
+        self.keys.push(Box::new(cmp));
+        self
+    }
+
+    /// 反转整个比较器的结果。
+    pub fn reverse(self) -> Self {
+        let keys = self.keys;
+        Comparator::new().then_with(move |a, b| compare_keys(&keys, b, a))
+    }
+
+    /// 按照已添加的键依次比较两个元素。
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        compare_keys(&self.keys, a, b)
+    }
+
+    /// 使用当前比较器对切片进行稳定排序。
+    pub fn sort(&self, slice: &mut [T]) {
+        slice.sort_by(|a, b| self.compare(a, b));
+    }
+
+    /// 使用当前比较器对切片进行不稳定排序。
+    pub fn sort_unstable(&self, slice: &mut [T]) {
+        slice.sort_unstable_by(|a, b| self.compare(a, b));
+    }
+
+    /// 在按当前比较器排好序的切片中二分查找与 `probe` 相等的元素。
+    ///
+    /// 返回值与 `slice::binary_search` 一致：找到时为 `Ok(index)`，否则为可插入位置 `Err(index)`。
+    pub fn binary_search(&self, slice: &[T], probe: &T) -> Result<usize, usize> {
+        slice.binary_search_by(|x| self.compare(x, probe))
+    }
+
+    /// 检查切片是否已按当前比较器排好序。
+    pub fn is_sorted(&self, slice: &[T]) -> bool {
+        slice.windows(2).all(|w| self.compare(&w[0], &w[1]).is_le())
+    }
+}
+
+impl<T> Compare<T> for Comparator<'_, T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        compare_keys(&self.keys, a, b)
+    }
+}
+
+fn compare_keys<T>(keys: &[KeyCmp<'_, T>], a: &T, b: &T) -> Ordering {
+    keys.iter()
+        .map(|cmp| cmp(a, b))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// 以按值提取的升序键开始构建比较器。
+pub fn by<'a, T: 'a, K, F>(f: F) -> Comparator<'a, T>
+where
+    K: Ord,
+    F: Fn(&T) -> K + Send + Sync + 'a,
+{
+    Comparator::new().then(f)
+}
+
+/// 以按值提取的降序键开始构建比较器。
+pub fn by_desc<'a, T: 'a, K, F>(f: F) -> Comparator<'a, T>
+where
+    K: Ord,
+    F: Fn(&T) -> K + Send + Sync + 'a,
+{
+    Comparator::new().then_desc(f)
+}
+
+/// 以借用的升序键开始构建比较器，例如 `by_ref(|p| &p.name)`。
+pub fn by_ref<'a, T: 'a, K, F>(f: F) -> Comparator<'a, T>
+where
+    K: Ord + ?Sized,
+    F: Fn(&T) -> &K + Send + Sync + 'a,
+{
+    Comparator::new().then_ref(f)
+}
+
+/// 以借用的降序键开始构建比较器。
+pub fn by_ref_desc<'a, T: 'a, K, F>(f: F) -> Comparator<'a, T>
+where
+    K: Ord + ?Sized,
+    F: Fn(&T) -> &K + Send + Sync + 'a,
+{
+    Comparator::new().then_ref_desc(f)
+}
+
+/// 以指定比较方式的升序字符串键开始构建比较器。
+pub fn by_str<'a, T: 'a, F>(f: F, order: StrOrder) -> Comparator<'a, T>
+where
+    F: Fn(&T) -> &str + Send + Sync + 'a,
+{
+    Comparator::new().then_str(f, order)
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Record {
+        name: String,
+        age: u32,
+        nickname: Option<String>,
+    }
+
+    fn record(name: &str, age: u32, nickname: Option<&str>) -> Record {
+        Record {
+            name: name.to_string(),
+            age,
+            nickname: nickname.map(str::to_string),
+        }
+    }
+
+    fn names(records: &[Record]) -> Vec<(&str, u32)> {
+        records.iter().map(|r| (r.name.as_str(), r.age)).collect()
+    }
+
+    /// 测试多键与方向组合
+    #[test]
+    fn multi_key_with_direction() {
+        let mut records = vec![
+            record("Zoe", 25, None),
+            record("Al", 1, None),
+            record("Al", 60, None),
+            record("John", 1, None),
+        ];
+
+        by_ref(|r: &Record| &r.name)
+            .then_desc(|r| r.age)
+            .sort(&mut records);
+        assert_eq!(
+            names(&records),
+            [("Al", 60), ("Al", 1), ("John", 1), ("Zoe", 25)]
+        );
+
+        by(|r: &Record| r.age)
+            .then_ref_desc(|r| r.name.as_str())
+            .sort(&mut records);
+        assert_eq!(
+            names(&records),
+            [("John", 1), ("Al", 1), ("Zoe", 25), ("Al", 60)]
+        );
+
+        let cmp = by(|r: &Record| r.age).then_ref_desc(|r| r.name.as_str());
+        cmp.reverse().sort(&mut records);
+        assert_eq!(
+            names(&records),
+            [("Al", 60), ("Zoe", 25), ("Al", 1), ("John", 1)]
+        );
+    }
+
+    /// 测试字符串比较方式
+    #[test]
+    fn string_orders() {
+        assert_eq!(StrOrder::Ordinal.compare("b", "A"), Ordering::Greater);
+        assert_eq!(
+            StrOrder::CaseInsensitive.compare("b", "A"),
+            Ordering::Greater
+        );
+        assert_eq!(
+            StrOrder::CaseInsensitive.compare("ÉMILE", "émile"),
+            Ordering::Equal
+        );
+        assert_eq!(
+            StrOrder::Dictionary.compare("apple", "Apple"),
+            Ordering::Less
+        );
+        assert_eq!(
+            StrOrder::Dictionary.compare("Apple", "banana"),
+            Ordering::Less
+        );
+
+        let mut words = vec!["banana", "Apple", "apple", "Banana"];
+        by_str(|w: &&str| w, StrOrder::Dictionary).sort(&mut words);
+        assert_eq!(words, ["apple", "Apple", "banana", "Banana"]);
+    }
+
+    /// 测试按键选择排序规则和自然排序
+    #[test]
+    fn collation_orders() {
+        let mut people = vec![
+            record("zoe", 1, Some("file10")),
+            record("Émile", 2, Some("file2")),
+            record("emile", 3, Some("File1")),
+        ];
+
+        by_str(
+            |r: &Record| &r.name,
+            StrOrder::Collation(Strength::Tertiary),
+        )
+        .sort(&mut people);
+        assert_eq!(names(&people), [("emile", 3), ("Émile", 2), ("zoe", 1)]);
+
+        // 名字在第一级相同，再按昵称自然降序
+        by_str(|r: &Record| &r.name, StrOrder::Collation(Strength::Primary))
+            .then_str_desc(
+                |r| r.nickname.as_deref().unwrap(),
+                StrOrder::Natural(Strength::Primary),
+            )
+            .sort(&mut people);
+        assert_eq!(names(&people), [("Émile", 2), ("emile", 3), ("zoe", 1)]);
+    }
+
+    /// 测试 None 的放置不受方向影响
+    #[test]
+    fn null_handling() {
+        let mut records = vec![
+            record("a", 1, Some("x")),
+            record("b", 2, None),
+            record("c", 3, Some("y")),
+        ];
+
+        Comparator::new()
+            .then_option(|r: &Record| r.nickname.clone(), Nulls::First)
+            .sort(&mut records);
+        assert_eq!(records[0].name, "b");
+        assert_eq!(records[2].name, "c");
+
+        Comparator::new()
+            .then_option_desc(|r: &Record| r.nickname.clone(), Nulls::Last)
+            .sort(&mut records);
+        assert_eq!(records[0].name, "c");
+        assert_eq!(records[2].name, "b");
+    }
+
+    /// 测试二分查找与 is_sorted
+    #[test]
+    fn binary_search_with_comparator() {
+        let mut records: Vec<Record> = (0..50)
+            .map(|i| record(&format!("n{}", i % 7), i, None))
+            .collect();
+        let cmp = by_ref(|r: &Record| &r.name).then_desc(|r| r.age);
+
+        assert!(!cmp.is_sorted(&records));
+        cmp.sort_unstable(&mut records);
+        assert!(cmp.is_sorted(&records));
+
+        for (i, r) in records.iter().enumerate() {
+            assert_eq!(cmp.binary_search(&records, r), Ok(i));
+        }
+        assert_eq!(
+            cmp.binary_search(&records, &record("n0", 100, None)),
+            Err(0)
+        );
+    }
+
+    /// 测试 Compare trait 对闭包与 DefaultOrder 的实现
+    #[test]
+    fn compare_trait_forms() {
+        fn max_by<T, C: Compare<T>>(slice: &[T], cmp: C) -> Option<&T> {
+            slice.iter().max_by(|a, b| cmp.compare(a, b))
+        }
+
+        let vec = vec![3, 9, 1];
+        assert_eq!(max_by(&vec, DefaultOrder), Some(&9));
+        assert_eq!(max_by(&vec, |a: &i32, b: &i32| b.cmp(a)), Some(&1));
+        assert_eq!(max_by(&vec, by(|x: &i32| x % 5)), Some(&9));
+    }
+}
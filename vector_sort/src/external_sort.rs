@@ -0,0 +1,493 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    path::PathBuf,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::comparator::Compare;
+use crate::heap;
+
+/// 默认的内存预算：64MB。
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+/// 默认的归并路数上限：一次归并最多同时打开的有序段数量。
+pub const DEFAULT_FAN_IN: usize = 128;
+
+/// 每个有序段的读写缓冲区大小。
+const RUN_BUFFER_SIZE: usize = 8 * 1024;
+
+/// 外部（磁盘）归并排序器，用于排序无法一次性放入内存的数据。
+///
+/// 排序分为两个阶段：
+/// 1. **生成有序段**：不断从输入中读取记录，当缓冲区估算的内存占用超过预算时，
+///    对缓冲区做稳定排序并写入临时文件；
+/// 2. **多路归并**：用一个小顶堆对所有临时文件做 k 路归并，以迭代器的形式逐条返回记录。
+///
+/// 一次归并同时打开的段数（归并路数）有上限，避免段数过多时耗尽文件描述符。
+/// 每个打开的段占用一个 8KB 的读缓冲区，这部分同样计入内存预算，因此归并路数取
+/// `memory_budget / 8KB` 与 [`fan_in`](ExternalSorter::fan_in) 中较小的一个（至少为 2）。
+/// 生成有序段时，同一层的段每凑满一次归并路数就被归并成一个更大的段，
+/// 只有最后一轮归并以迭代器的形式流式返回。
+///
+/// 临时文件是匿名文件，迭代器被丢弃后由操作系统自动回收。
+/// 记录通过 `bincode` 序列化，内存占用按序列化后的字节数加上 `size_of::<T>()` 估算。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::external_sort::ExternalSorter;
+///
+/// let records = (0..10_000u32).rev();
+///
+/// // 使用很小的内存预算，强制把数据分成多个临时文件
+/// let sorted = ExternalSorter::new()
+///     .memory_budget(4 * 1024)
+///     .sort_by(records, |a: &u32, b: &u32| a.cmp(b))
+///     .unwrap()
+///     .collect::<std::io::Result<Vec<u32>>>()
+///     .unwrap();
+///
+/// assert_eq!(sorted, (0..10_000).collect::<Vec<u32>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExternalSorter {
+    memory_budget: usize,
+    fan_in: usize,
+    temp_dir: Option<PathBuf>,
+}
+
+impl Default for ExternalSorter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExternalSorter {
+    /// 使用默认内存预算和系统临时目录创建排序器。
+    pub fn new() -> Self {
+        ExternalSorter {
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            fan_in: DEFAULT_FAN_IN,
+            temp_dir: None,
+        }
+    }
+
+    /// 设置内存预算（字节），缓冲区超过该值时写出一个有序段。
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes.max(1);
+        self
+    }
+
+    /// 设置归并路数上限，即一次归并最多同时打开的有序段数量，最小为 2。
+    pub fn fan_in(mut self, runs: usize) -> Self {
+        self.fan_in = runs.max(2);
+        self
+    }
+
+    /// 设置存放临时文件的目录，默认使用系统临时目录。
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = Some(dir.into());
+        self
+    }
+
+    /// 使用比较器对 `records` 做稳定的外部排序。
+    ///
+    /// # 参数
+    ///
+    /// * `records` - 要排序的记录，可以是任意迭代器。
+    /// * `cmp` - 比较器，可以是 `sort_by` 形式的闭包或 [`Comparator`](crate::comparator::Comparator)。
+    ///
+    /// # 返回值
+    ///
+    /// 返回按比较器排好序的记录迭代器。写临时文件失败时返回错误；
+    /// 归并过程中读取临时文件失败时，迭代器产出一个 `Err` 后结束。
+    pub fn sort_by<T, I, C>(&self, records: I, cmp: C) -> io::Result<SortedIter<T, C>>
+    where
+        T: Serialize + DeserializeOwned,
+        I: IntoIterator<Item = T>,
+        C: Compare<T>,
+    {
+        let fan_in = self.effective_fan_in();
+        // levels[i] 中的段由 fan_in^i 个初始段归并而来；高层的段总是早于低层的段生成
+        let mut levels: Vec<Vec<Run<T>>> = Vec::new();
+        let mut buffer = Vec::new();
+        let mut buffered_bytes = 0;
+
+        for record in records {
+            buffered_bytes += estimate_size(&record)?;
+            buffer.push(record);
+
+            if buffered_bytes >= self.memory_budget {
+                let run = self.spill(&mut buffer, &cmp)?;
+                self.push_run(&mut levels, run, fan_in, &cmp)?;
+                buffered_bytes = 0;
+            }
+        }
+
+        // 所有数据都能放进内存时无需读写磁盘
+        if levels.is_empty() {
+            buffer.sort_by(|a, b| cmp.compare(a, b));
+            return Ok(SortedIter::InMemory(buffer.into_iter()));
+        }
+
+        if !buffer.is_empty() {
+            let run = self.spill(&mut buffer, &cmp)?;
+            self.push_run(&mut levels, run, fan_in, &cmp)?;
+        }
+
+        // 按生成顺序排列剩余的段，超过归并路数时逐轮把相邻的段归并到中间文件
+        let mut runs: Vec<Run<T>> = levels.into_iter().rev().flatten().collect();
+        while runs.len() > fan_in {
+            let mut merged = Vec::with_capacity(runs.len().div_ceil(fan_in));
+            let mut rest = runs.into_iter();
+            loop {
+                let group: Vec<Run<T>> = rest.by_ref().take(fan_in).collect();
+                if group.is_empty() {
+                    break;
+                }
+                merged.push(self.merge_runs(group, &cmp)?);
+            }
+            runs = merged;
+        }
+
+        Ok(SortedIter::Merge(RunMerger::new(runs, cmp)?))
+    }
+
+    /// 归并路数：不超过 `fan_in`，且所有段的读缓冲区不超过内存预算。
+    fn effective_fan_in(&self) -> usize {
+        (self.memory_budget / RUN_BUFFER_SIZE).clamp(2, self.fan_in)
+    }
+
+    /// 把新生成的段放入第 0 层；某一层凑满 `fan_in` 个段时，把它们归并成上一层的一个段。
+    fn push_run<T, C>(
+        &self,
+        levels: &mut Vec<Vec<Run<T>>>,
+        mut run: Run<T>,
+        fan_in: usize,
+        cmp: &C,
+    ) -> io::Result<()>
+    where
+        T: Serialize + DeserializeOwned,
+        C: Compare<T>,
+    {
+        for level in 0.. {
+            if level == levels.len() {
+                levels.push(Vec::new());
+            }
+            levels[level].push(run);
+            if levels[level].len() < fan_in {
+                break;
+            }
+            let group = std::mem::take(&mut levels[level]);
+            run = self.merge_runs(group, cmp)?;
+        }
+        Ok(())
+    }
+
+    /// 把若干个按生成顺序排列的段归并成一个新段。
+    fn merge_runs<T, C>(&self, runs: Vec<Run<T>>, cmp: &C) -> io::Result<Run<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        C: Compare<T>,
+    {
+        let merger = RunMerger::new(runs, |a: &T, b: &T| cmp.compare(a, b))?;
+        self.write_run(merger)
+    }
+
+    /// 对缓冲区排序并写入一个新的临时文件，写完后清空缓冲区。
+    fn spill<T, C>(&self, buffer: &mut Vec<T>, cmp: &C) -> io::Result<Run<T>>
+    where
+        T: Serialize,
+        C: Compare<T>,
+    {
+        buffer.sort_by(|a, b| cmp.compare(a, b));
+        self.write_run(buffer.drain(..).map(Ok))
+    }
+
+    /// 把有序的记录依次写入一个新的临时文件。
+    fn write_run<T, I>(&self, records: I) -> io::Result<Run<T>>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = io::Result<T>>,
+    {
+        let file = match &self.temp_dir {
+            Some(dir) => tempfile::tempfile_in(dir)?,
+            None => tempfile::tempfile()?,
+        };
+        let mut writer = BufWriter::with_capacity(RUN_BUFFER_SIZE, file);
+        let mut len = 0;
+        for record in records {
+            bincode::serialize_into(&mut writer, &record?).map_err(|e| into_io_error(*e))?;
+            len += 1;
+        }
+        writer.flush()?;
+
+        let mut file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(Run {
+            reader: BufReader::with_capacity(RUN_BUFFER_SIZE, file),
+            remaining: len,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// 估算一条记录在内存中的大小。
+fn estimate_size<T: Serialize>(record: &T) -> io::Result<usize> {
+    let serialized = bincode::serialized_size(record).map_err(|e| into_io_error(*e))?;
+    Ok(serialized as usize + std::mem::size_of::<T>())
+}
+
+fn into_io_error(e: bincode::ErrorKind) -> io::Error {
+    match e {
+        bincode::ErrorKind::Io(e) => e,
+        other => io::Error::new(io::ErrorKind::InvalidData, other),
+    }
+}
+
+/// 写入临时文件的一个有序段。
+struct Run<T> {
+    reader: BufReader<File>,
+    remaining: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Run<T> {
+    /// 读取下一条记录，段已读完时返回 `None`。
+    fn next_record(&mut self) -> io::Result<Option<T>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        bincode::deserialize_from(&mut self.reader)
+            .map(Some)
+            .map_err(|e| into_io_error(*e))
+    }
+}
+
+/// 外部排序的结果迭代器。
+pub enum SortedIter<T, C> {
+    /// 数据全部放在内存中，没有产生临时文件。
+    InMemory(std::vec::IntoIter<T>),
+    /// 对多个临时文件做 k 路归并，同时打开的临时文件不超过归并路数。
+    Merge(RunMerger<T, C>),
+}
+
+impl<T, C> Iterator for SortedIter<T, C>
+where
+    T: DeserializeOwned,
+    C: Compare<T>,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SortedIter::InMemory(iter) => iter.next().map(Ok),
+            SortedIter::Merge(merger) => merger.next(),
+        }
+    }
+}
+
+/// 基于小顶堆的 k 路归并器。
+///
+/// 堆中保存每个有序段当前的首条记录以及段的编号；比较相等时编号小的段优先，
+/// 由于段是按输入顺序生成的，这保证了整个外部排序的稳定性。
+pub struct RunMerger<T, C> {
+    runs: Vec<Run<T>>,
+    heap: Vec<(T, usize)>,
+    cmp: C,
+    failed: bool,
+}
+
+impl<T, C> RunMerger<T, C>
+where
+    T: DeserializeOwned,
+    C: Compare<T>,
+{
+    fn new(mut runs: Vec<Run<T>>, cmp: C) -> io::Result<Self> {
+        let mut heap = Vec::with_capacity(runs.len());
+        for (i, run) in runs.iter_mut().enumerate() {
+            if let Some(record) = run.next_record()? {
+                heap.push((record, i));
+            }
+        }
+
+        let mut merger = RunMerger {
+            runs,
+            heap,
+            cmp,
+            failed: false,
+        };
+        let cmp = &merger.cmp;
+        heap::heapify(&mut merger.heap, &|a, b| run_less(cmp, a, b));
+        Ok(merger)
+    }
+}
+
+/// 堆中元素的顺序：先按比较器，再按段编号。
+fn run_less<T, C: Compare<T>>(cmp: &C, (x, i): &(T, usize), (y, j): &(T, usize)) -> bool {
+    cmp.compare(x, y).then(i.cmp(j)).is_lt()
+}
+
+impl<T, C> Iterator for RunMerger<T, C>
+where
+    T: DeserializeOwned,
+    C: Compare<T>,
+{
+    type Item = io::Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.heap.is_empty() {
+            return None;
+        }
+
+        let run = self.heap[0].1;
+        let record = match self.runs[run].next_record() {
+            // 用同一段的下一条记录替换堆顶
+            Ok(Some(next)) => std::mem::replace(&mut self.heap[0].0, next),
+            // 该段已读完，把最后一个元素移到堆顶
+            Ok(None) => self.heap.swap_remove(0).0,
+            Err(e) => {
+                self.failed = true;
+                return Some(Err(e));
+            }
+        };
+
+        let cmp = &self.cmp;
+        heap::sift_down(&mut self.heap, 0, &|a, b| run_less(cmp, a, b));
+        Some(Ok(record))
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::{by, by_ref, DefaultOrder};
+    use rand::{thread_rng, Rng};
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        name: String,
+        age: u32,
+        seq: usize,
+    }
+
+    fn collect<T, C>(iter: SortedIter<T, C>) -> Vec<T>
+    where
+        T: DeserializeOwned,
+        C: Compare<T>,
+    {
+        iter.collect::<io::Result<Vec<T>>>().unwrap()
+    }
+
+    /// 测试数据能全部放入内存时不会产生临时文件
+    #[test]
+    fn in_memory_path() {
+        let iter = ExternalSorter::new()
+            .sort_by(vec![3, 1, 2], DefaultOrder)
+            .unwrap();
+
+        assert!(matches!(iter, SortedIter::InMemory(_)));
+        assert_eq!(collect(iter), [1, 2, 3]);
+
+        let empty = ExternalSorter::new()
+            .sort_by(Vec::<i32>::new(), DefaultOrder)
+            .unwrap();
+        assert!(collect(empty).is_empty());
+    }
+
+    /// 测试多段归并的结果与内存排序一致
+    #[test]
+    fn spills_and_merges() {
+        let mut rng = thread_rng();
+        let input: Vec<i64> = (0..20_000).map(|_| rng.gen_range(-500..500)).collect();
+        let mut expected = input.clone();
+        expected.sort();
+
+        let dir = tempfile::tempdir().unwrap();
+        let iter = ExternalSorter::new()
+            .memory_budget(8 * 1024)
+            .temp_dir(dir.path())
+            .sort_by(input, DefaultOrder)
+            .unwrap();
+
+        assert!(matches!(iter, SortedIter::Merge(_)));
+        assert_eq!(collect(iter), expected);
+    }
+
+    /// 测试外部排序的稳定性以及对 Comparator 的复用
+    #[test]
+    fn stable_with_comparator() {
+        let mut rng = thread_rng();
+        let input: Vec<Record> = (0..5_000)
+            .map(|seq| Record {
+                name: format!("n{}", rng.gen_range(0..10)),
+                age: rng.gen_range(0..5),
+                seq,
+            })
+            .collect();
+
+        let cmp = by_ref(|r: &Record| &r.name).then_desc(|r| r.age);
+        let mut expected = input.clone();
+        cmp.sort(&mut expected);
+
+        let sorted = collect(
+            ExternalSorter::new()
+                .memory_budget(16 * 1024)
+                .sort_by(input.clone(), cmp)
+                .unwrap(),
+        );
+        assert_eq!(sorted, expected);
+
+        let by_age = collect(
+            ExternalSorter::new()
+                .memory_budget(1)
+                .sort_by(input, by(|r: &Record| r.age))
+                .unwrap(),
+        );
+        assert!(by_age
+            .windows(2)
+            .all(|w| w[0].age < w[1].age || (w[0].age == w[1].age && w[0].seq < w[1].seq)));
+    }
+
+    /// 测试归并路数考虑读缓冲区占用的内存
+    #[test]
+    fn fan_in_counts_read_buffers() {
+        assert_eq!(ExternalSorter::new().effective_fan_in(), DEFAULT_FAN_IN);
+        assert_eq!(ExternalSorter::new().memory_budget(1).effective_fan_in(), 2);
+        assert_eq!(
+            ExternalSorter::new()
+                .memory_budget(8 * RUN_BUFFER_SIZE)
+                .effective_fan_in(),
+            8
+        );
+        assert_eq!(ExternalSorter::new().fan_in(0).effective_fan_in(), 2);
+    }
+
+    /// 测试有序段数量超过归并路数时分多轮归并，结果仍然稳定
+    #[test]
+    fn more_runs_than_fan_in() {
+        let mut rng = thread_rng();
+        let input: Vec<(u8, u32)> = (0..60_000).map(|seq| (rng.gen_range(0..50), seq)).collect();
+        let mut expected = input.clone();
+        expected.sort_by_key(|&(key, _)| key);
+
+        let sorter = ExternalSorter::new()
+            .memory_budget(4 * RUN_BUFFER_SIZE)
+            .fan_in(4);
+        assert_eq!(sorter.effective_fan_in(), 4);
+
+        let sorted = collect(
+            sorter
+                .sort_by(input, |a: &(u8, u32), b: &(u8, u32)| a.0.cmp(&b.0))
+                .unwrap(),
+        );
+        assert_eq!(sorted, expected);
+    }
+}
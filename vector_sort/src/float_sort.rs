@@ -0,0 +1,327 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+
+/// 支持 IEEE 754 totalOrder 全序比较的浮点类型。
+///
+/// totalOrder 的顺序为：
+/// `-NaN < -inf < 负数 < -0.0 < +0.0 < 正数 < +inf < +NaN`
+///
+/// 与 `partial_cmp` 不同，任意两个值（包括 NaN）之间都存在确定的顺序，
+/// 因此可以安全地用于 `sort_by` 等需要全序的场景。
+pub trait TotalFloat: Copy + PartialOrd {
+    /// 按照 IEEE 754 totalOrder 比较两个浮点数。
+    fn total_order(&self, other: &Self) -> Ordering;
+
+    /// 判断当前值是否为 NaN。
+    fn is_nan_value(&self) -> bool;
+
+    /// 返回浮点数的位表示，用于哈希和按位相等判断。
+    fn to_bits_u64(&self) -> u64;
+}
+
+impl TotalFloat for f32 {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+
+    fn is_nan_value(&self) -> bool {
+        self.is_nan()
+    }
+
+    fn to_bits_u64(&self) -> u64 {
+        u64::from(self.to_bits())
+    }
+}
+
+impl TotalFloat for f64 {
+    fn total_order(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+
+    fn is_nan_value(&self) -> bool {
+        self.is_nan()
+    }
+
+    fn to_bits_u64(&self) -> u64 {
+        self.to_bits()
+    }
+}
+
+/// 排序时 NaN 的处理方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPlacement {
+    /// 所有 NaN 排在最前面。
+    First,
+    /// 所有 NaN 排在最后面。
+    Last,
+    /// 从结果中移除所有 NaN。
+    Filter,
+}
+
+/// 按照 IEEE 754 totalOrder 对浮点切片进行稳定排序。
+///
+/// 负号位的 NaN 排在最前，正号位的 NaN 排在最后，`-0.0` 排在 `+0.0` 之前。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的浮点切片。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::float_sort::sort_floats_total;
+///
+/// let mut vec = vec![2.0, f64::NAN, -0.0, 0.0, f64::NEG_INFINITY];
+/// sort_floats_total(&mut vec);
+///
+/// assert_eq!(vec[0], f64::NEG_INFINITY);
+/// assert!(vec[1].is_sign_negative() && vec[1] == 0.0);
+/// assert!(vec[2].is_sign_positive() && vec[2] == 0.0);
+/// assert_eq!(vec[3], 2.0);
+/// assert!(vec[4].is_nan());
+/// ```
+pub fn sort_floats_total<T: TotalFloat>(slice: &mut [T]) {
+    slice.sort_by(|a, b| a.total_order(b));
+}
+
+/// 对浮点向量排序，并按照 `nan` 指定的方式放置 NaN。
+///
+/// 非 NaN 的值按照 totalOrder 排序（即 `-0.0` 排在 `+0.0` 之前），
+/// 所有 NaN 不论符号位都视为同一类，按 `nan` 的要求统一放到开头、结尾或直接移除。
+///
+/// # 参数
+///
+/// * `vec` - 一个可变引用，指向要排序的浮点向量。
+/// * `nan` - NaN 的放置方式。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::float_sort::{sort_floats, NanPlacement};
+///
+/// let mut vec = vec![3.0, f64::NAN, 1.0, -f64::NAN, 2.0];
+/// sort_floats(&mut vec, NanPlacement::Filter);
+///
+/// assert_eq!(vec, vec![1.0, 2.0, 3.0]);
+/// ```
+pub fn sort_floats<T: TotalFloat>(vec: &mut Vec<T>, nan: NanPlacement) {
+    match nan {
+        NanPlacement::Filter => {
+            vec.retain(|x| !x.is_nan_value());
+            sort_floats_total(vec);
+        }
+        NanPlacement::First => vec.sort_by(|a, b| nan_first_cmp(a, b)),
+        NanPlacement::Last => vec.sort_by(|a, b| nan_last_cmp(a, b)),
+    }
+}
+
+/// NaN 视为最小值的比较函数，其余值按 totalOrder 比较。
+fn nan_first_cmp<T: TotalFloat>(a: &T, b: &T) -> Ordering {
+    match (a.is_nan_value(), b.is_nan_value()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.total_order(b),
+    }
+}
+
+/// NaN 视为最大值的比较函数，其余值按 totalOrder 比较。
+fn nan_last_cmp<T: TotalFloat>(a: &T, b: &T) -> Ordering {
+    match (a.is_nan_value(), b.is_nan_value()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.total_order(b),
+    }
+}
+
+/// 以 IEEE 754 totalOrder 实现 `Ord` 的浮点包装类型。
+///
+/// 可以直接用于 `sort`、`BTreeMap`、`BinaryHeap` 以及 `HashMap` 等需要 `Ord`/`Hash` 的场景。
+/// 两个值相等当且仅当它们的位表示相同，因此 `-0.0 != +0.0`，而相同位表示的 NaN 彼此相等。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::float_sort::OrderedFloat;
+///
+/// let mut vec = vec![OrderedFloat(1.5), OrderedFloat(f64::NAN), OrderedFloat(-1.0)];
+/// vec.sort();
+///
+/// assert_eq!(vec[0], OrderedFloat(-1.0));
+/// assert_eq!(vec[1], OrderedFloat(1.5));
+/// assert!(vec[2].0.is_nan());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OrderedFloat<T>(pub T);
+
+impl<T: TotalFloat> OrderedFloat<T> {
+    /// 取出内部的浮点值。
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: TotalFloat> From<T> for OrderedFloat<T> {
+    fn from(value: T) -> Self {
+        OrderedFloat(value)
+    }
+}
+
+impl<T: TotalFloat> PartialEq for OrderedFloat<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.total_order(&other.0) == Ordering::Equal
+    }
+}
+
+impl<T: TotalFloat> Eq for OrderedFloat<T> {}
+
+impl<T: TotalFloat> PartialOrd for OrderedFloat<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: TotalFloat> Ord for OrderedFloat<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_order(&other.0)
+    }
+}
+
+impl<T: TotalFloat> Hash for OrderedFloat<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits_u64().hash(state);
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+    use std::collections::HashSet;
+
+    /// 生成混合了 NaN、无穷大和正负零的随机浮点向量
+    fn random_floats(len: usize) -> Vec<f64> {
+        const SPECIAL: [f64; 6] = [
+            f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            0.0,
+            -0.0,
+            f64::MIN_POSITIVE,
+        ];
+
+        let mut rng = thread_rng();
+
+        (0..len)
+            .map(|_| {
+                if rng.gen_bool(0.3) {
+                    let v = *SPECIAL.choose(&mut rng).unwrap();
+                    // NaN 也随机带上负号位
+                    if rng.gen() {
+                        -v
+                    } else {
+                        v
+                    }
+                } else {
+                    rng.gen_range(-1e6..1e6)
+                }
+            })
+            .collect()
+    }
+
+    /// 统计向量中每个位表示出现的次数，用于校验排序前后是同一个排列
+    fn bit_multiset(vec: &[f64]) -> Vec<u64> {
+        let mut bits: Vec<u64> = vec.iter().map(|x| x.to_bits()).collect();
+        bits.sort_unstable();
+        bits
+    }
+
+    /// 测试 totalOrder 排序不会 panic，且结果有序并保持原有元素
+    #[test]
+    fn total_order_property() {
+        for len in 0..200 {
+            let original = random_floats(len);
+            let mut vec = original.clone();
+
+            sort_floats_total(&mut vec);
+
+            assert!(vec.windows(2).all(|w| w[0].total_cmp(&w[1]).is_le()));
+            assert_eq!(bit_multiset(&vec), bit_multiset(&original));
+        }
+    }
+
+    /// 测试 -0.0 排在 +0.0 之前，负 NaN 在最前、正 NaN 在最后
+    #[test]
+    fn signed_zero_and_nan_sign() {
+        let mut vec = vec![
+            0.0,
+            f64::NAN,
+            -0.0,
+            -f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ];
+
+        sort_floats_total(&mut vec);
+
+        assert!(vec[0].is_nan() && vec[0].is_sign_negative());
+        assert_eq!(vec[1], f64::NEG_INFINITY);
+        assert!(vec[2] == 0.0 && vec[2].is_sign_negative());
+        assert!(vec[3] == 0.0 && vec[3].is_sign_positive());
+        assert_eq!(vec[4], f64::INFINITY);
+        assert!(vec[5].is_nan() && vec[5].is_sign_positive());
+    }
+
+    /// 测试三种 NaN 放置方式
+    #[test]
+    fn nan_placement_property() {
+        for len in 0..200 {
+            let original = random_floats(len);
+            let nan_count = original.iter().filter(|x| x.is_nan()).count();
+
+            let mut first = original.clone();
+            sort_floats(&mut first, NanPlacement::First);
+            assert!(first[..nan_count].iter().all(|x| x.is_nan()));
+            assert!(first[nan_count..]
+                .windows(2)
+                .all(|w| w[0].total_cmp(&w[1]).is_le()));
+            assert_eq!(bit_multiset(&first), bit_multiset(&original));
+
+            let mut last = original.clone();
+            sort_floats(&mut last, NanPlacement::Last);
+            let split = len - nan_count;
+            assert!(last[split..].iter().all(|x| x.is_nan()));
+            assert!(last[..split]
+                .windows(2)
+                .all(|w| w[0].total_cmp(&w[1]).is_le()));
+            assert_eq!(bit_multiset(&last), bit_multiset(&original));
+
+            let mut filtered = original.clone();
+            sort_floats(&mut filtered, NanPlacement::Filter);
+            assert_eq!(filtered.len(), split);
+            assert_eq!(&filtered[..], &last[..split]);
+        }
+    }
+
+    /// 测试 OrderedFloat 的 Ord、Eq 与 Hash 保持一致
+    #[test]
+    fn ordered_float_consistency() {
+        let mut vec: Vec<OrderedFloat<f32>> = [1.0f32, f32::NAN, -0.0, 0.0, f32::NEG_INFINITY]
+            .into_iter()
+            .map(OrderedFloat::from)
+            .collect();
+        vec.sort();
+
+        assert_eq!(vec[0], OrderedFloat(f32::NEG_INFINITY));
+        assert_ne!(vec[1], vec[2]);
+        assert!(vec[4].into_inner().is_nan());
+        assert_eq!(vec[4], OrderedFloat(f32::NAN));
+
+        let set: HashSet<OrderedFloat<f32>> = vec.into_iter().collect();
+        assert!(set.contains(&OrderedFloat(f32::NAN)));
+        assert!(set.contains(&OrderedFloat(-0.0)));
+        assert_eq!(set.len(), 5);
+    }
+}
@@ -0,0 +1,54 @@
+//! 以比较函数为参数的二叉堆操作。
+//!
+//! 标准库的 `BinaryHeap` 只能使用元素自身的 `Ord`，而排序接口需要支持任意比较器，
+//! 因此这里提供直接作用于切片的堆操作，`less(a, b)` 为真表示 `a` 应该更靠近堆顶。
+
+/// 把下标 `i` 处的元素向下调整到合适的位置。
+pub(crate) fn sift_down<T, F>(heap: &mut [T], mut i: usize, less: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    loop {
+        let left = 2 * i + 1;
+        let right = left + 1;
+        let mut top = i;
+
+        if left < heap.len() && less(&heap[left], &heap[top]) {
+            top = left;
+        }
+        if right < heap.len() && less(&heap[right], &heap[top]) {
+            top = right;
+        }
+        if top == i {
+            return;
+        }
+
+        heap.swap(i, top);
+        i = top;
+    }
+}
+
+/// 把下标 `i` 处的元素向上调整到合适的位置。
+pub(crate) fn sift_up<T, F>(heap: &mut [T], mut i: usize, less: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if !less(&heap[i], &heap[parent]) {
+            return;
+        }
+        heap.swap(i, parent);
+        i = parent;
+    }
+}
+
+/// 在线性时间内把任意切片整理成堆。
+pub(crate) fn heapify<T, F>(heap: &mut [T], less: &F)
+where
+    F: Fn(&T, &T) -> bool,
+{
+    for i in (0..heap.len() / 2).rev() {
+        sift_down(heap, i, less);
+    }
+}
@@ -0,0 +1,744 @@
+//! 带统计的经典排序算法，用于教学和分析算法行为。
+//!
+//! 每个算法都只通过少数几个基本操作访问数组：比较两个位置、交换两个位置、
+//! 把一个值写入某个位置。这些操作会被计数，并可以记录成逐步的 [`Trace`]，
+//! 之后导出为 JSON，或用 [`Animation`] 在终端中以柱状图动画回放。
+//!
+//! 这里的实现以清晰为主，不追求性能，日常排序请使用标准库或本 crate 的其他模块。
+
+use std::{fmt, io::Write, str::FromStr, thread, time::Duration};
+
+use ansi_term::{Colour, Style};
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::comparator::Compare;
+
+/// 可用的排序算法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Algorithm {
+    /// 插入排序，稳定，O(n²)。
+    Insertion,
+    /// 自顶向下归并排序，稳定，O(n log n)，需要 O(n) 辅助空间。
+    Merge,
+    /// 三数取中的快速排序，不稳定，平均 O(n log n)。
+    Quick,
+    /// 堆排序，不稳定，O(n log n)，原地。
+    Heap,
+    /// 使用 Ciura 间隔序列的希尔排序，不稳定。
+    Shell,
+    /// 简化的 Timsort：识别自然有序段，短段用插入排序补足，再按栈规则归并，稳定。
+    Tim,
+}
+
+impl Algorithm {
+    /// 所有算法，按上面的声明顺序。
+    pub const ALL: [Algorithm; 6] = [
+        Algorithm::Insertion,
+        Algorithm::Merge,
+        Algorithm::Quick,
+        Algorithm::Heap,
+        Algorithm::Shell,
+        Algorithm::Tim,
+    ];
+
+    /// 算法名称，与 [`FromStr`] 接受的写法一致。
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Insertion => "insertion",
+            Algorithm::Merge => "merge",
+            Algorithm::Quick => "quick",
+            Algorithm::Heap => "heap",
+            Algorithm::Shell => "shell",
+            Algorithm::Tim => "tim",
+        }
+    }
+
+    /// 算法是否稳定（相等元素保持原有顺序）。
+    pub fn is_stable(self) -> bool {
+        matches!(
+            self,
+            Algorithm::Insertion | Algorithm::Merge | Algorithm::Tim
+        )
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match Algorithm::ALL
+            .into_iter()
+            .find(|a| a.name().eq_ignore_ascii_case(s))
+        {
+            Some(algorithm) => Ok(algorithm),
+            None => bail!(
+                "unknown algorithm `{s}`, expected one of: insertion, merge, quick, heap, shell, tim"
+            ),
+        }
+    }
+}
+
+/// 排序过程中基本操作的次数。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    /// 比较次数。
+    pub comparisons: u64,
+    /// 交换次数。
+    pub swaps: u64,
+    /// 单独写入次数（归并时把元素写回数组）。
+    pub writes: u64,
+}
+
+/// 排序过程中的一步操作。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Step<T> {
+    /// 比较位置 `i` 和 `j` 上的元素。
+    Compare { i: usize, j: usize },
+    /// 交换位置 `i` 和 `j` 上的元素。
+    Swap { i: usize, j: usize },
+    /// 把 `value` 写入位置 `i`。
+    Set { i: usize, value: T },
+}
+
+/// 一次排序的完整记录，可以直接序列化为 JSON。
+#[derive(Debug, Clone, Serialize)]
+pub struct Trace<T> {
+    /// 使用的算法。
+    pub algorithm: Algorithm,
+    /// 排序前的数组。
+    pub initial: Vec<T>,
+    /// 按顺序发生的所有操作。
+    pub steps: Vec<Step<T>>,
+    /// 操作计数。
+    pub stats: Stats,
+}
+
+impl<T: Clone> Trace<T> {
+    /// 从初始数组开始依次执行所有操作，返回最终的数组，应与排序结果一致。
+    pub fn replay(&self) -> Vec<T> {
+        let mut state = self.initial.clone();
+        for step in &self.steps {
+            apply(&mut state, step);
+        }
+        state
+    }
+}
+
+fn apply<T: Clone>(state: &mut [T], step: &Step<T>) {
+    match step {
+        Step::Compare { .. } => {}
+        Step::Swap { i, j } => state.swap(*i, *j),
+        Step::Set { i, value } => state[*i] = value.clone(),
+    }
+}
+
+/// 用指定的算法排序，返回操作计数。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+/// * `algorithm` - 使用的排序算法。
+/// * `cmp` - 比较器，可以是 `sort_by` 形式的闭包或 [`Comparator`](crate::comparator::Comparator)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::comparator::DefaultOrder;
+/// use vector_sort::instrumented::{sort_counted, Algorithm};
+///
+/// let mut vec = vec![1, 2, 3, 5, 4];
+/// let stats = sort_counted(&mut vec, Algorithm::Insertion, DefaultOrder);
+///
+/// assert_eq!(vec, [1, 2, 3, 4, 5]);
+/// assert_eq!(stats.comparisons, 5);
+/// assert_eq!(stats.swaps, 1);
+/// ```
+pub fn sort_counted<T: Clone, C: Compare<T>>(
+    slice: &mut [T],
+    algorithm: Algorithm,
+    cmp: C,
+) -> Stats {
+    let mut sorter = Sorter {
+        slice,
+        cmp,
+        stats: Stats::default(),
+        steps: None,
+    };
+    sorter.run(algorithm);
+    sorter.stats
+}
+
+/// 用指定的算法排序，并记录每一步操作。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::comparator::DefaultOrder;
+/// use vector_sort::instrumented::{sort_traced, Algorithm, Step};
+///
+/// let mut vec = vec![2, 1];
+/// let trace = sort_traced(&mut vec, Algorithm::Insertion, DefaultOrder);
+///
+/// assert_eq!(trace.steps, [Step::Compare { i: 1, j: 0 }, Step::Swap { i: 1, j: 0 }]);
+/// assert_eq!(trace.replay(), vec);
+///
+/// let json = serde_json::to_string(&trace).unwrap();
+/// assert!(json.starts_with(r#"{"algorithm":"insertion","initial":[2,1],"steps":[{"op":"compare""#));
+/// ```
+pub fn sort_traced<T: Clone, C: Compare<T>>(
+    slice: &mut [T],
+    algorithm: Algorithm,
+    cmp: C,
+) -> Trace<T> {
+    let initial = slice.to_vec();
+    let mut sorter = Sorter {
+        slice,
+        cmp,
+        stats: Stats::default(),
+        steps: Some(Vec::new()),
+    };
+    sorter.run(algorithm);
+
+    Trace {
+        algorithm,
+        initial,
+        steps: sorter.steps.unwrap_or_default(),
+        stats: sorter.stats,
+    }
+}
+
+/// 希尔排序的 Ciura 间隔序列。
+const SHELL_GAPS: [usize; 8] = [701, 301, 132, 57, 23, 10, 4, 1];
+
+/// Timsort 中自然有序段的最小长度，短于它的段用插入排序补足。
+const MIN_RUN: usize = 16;
+
+struct Sorter<'s, T, C> {
+    slice: &'s mut [T],
+    cmp: C,
+    stats: Stats,
+    steps: Option<Vec<Step<T>>>,
+}
+
+impl<T: Clone, C: Compare<T>> Sorter<'_, T, C> {
+    fn run(&mut self, algorithm: Algorithm) {
+        let len = self.slice.len();
+        match algorithm {
+            Algorithm::Insertion => self.insertion(0, len, 1),
+            Algorithm::Merge => self.merge_sort(0, len),
+            Algorithm::Quick => self.quick(0, len),
+            Algorithm::Heap => self.heap(),
+            Algorithm::Shell => self.shell(),
+            Algorithm::Tim => self.tim(),
+        }
+    }
+
+    fn record(&mut self, step: Step<T>) {
+        if let Some(steps) = self.steps.as_mut() {
+            steps.push(step);
+        }
+    }
+
+    /// 位置 `i` 上的元素是否严格小于位置 `j` 上的元素。
+    fn less(&mut self, i: usize, j: usize) -> bool {
+        self.stats.comparisons += 1;
+        self.record(Step::Compare { i, j });
+        self.cmp.compare(&self.slice[i], &self.slice[j]).is_lt()
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        if i == j {
+            return;
+        }
+        self.stats.swaps += 1;
+        self.record(Step::Swap { i, j });
+        self.slice.swap(i, j);
+    }
+
+    fn set(&mut self, i: usize, value: T) {
+        self.stats.writes += 1;
+        if self.steps.is_some() {
+            self.record(Step::Set {
+                i,
+                value: value.clone(),
+            });
+        }
+        self.slice[i] = value;
+    }
+
+    /// 对 `[lo, hi)` 做间隔为 `gap` 的插入排序，`gap` 为 1 时即普通插入排序。
+    fn insertion(&mut self, lo: usize, hi: usize, gap: usize) {
+        for i in lo + gap..hi {
+            let mut j = i;
+            while j >= lo + gap && self.less(j, j - gap) {
+                self.swap(j, j - gap);
+                j -= gap;
+            }
+        }
+    }
+
+    fn shell(&mut self) {
+        let len = self.slice.len();
+        for gap in SHELL_GAPS.into_iter().filter(|&gap| gap < len.max(2)) {
+            self.insertion(0, len, gap);
+        }
+    }
+
+    fn merge_sort(&mut self, lo: usize, hi: usize) {
+        if hi - lo < 2 {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.merge_sort(lo, mid);
+        self.merge_sort(mid, hi);
+        self.merge(lo, mid, hi);
+    }
+
+    /// 归并相邻的有序段 `[lo, mid)` 和 `[mid, hi)`。
+    ///
+    /// 先完成全部比较、确定结果中每个位置的来源，再把元素依次写回，
+    /// 这样比较器中途 panic 时数组仍是原有元素的一个排列。
+    fn merge(&mut self, lo: usize, mid: usize, hi: usize) {
+        let mut sources = Vec::with_capacity(hi - lo);
+        let (mut i, mut j) = (lo, mid);
+
+        while i < mid && j < hi {
+            // 右段元素严格更小时才先取它，保证稳定
+            if self.less(j, i) {
+                sources.push(j);
+                j += 1;
+            } else {
+                sources.push(i);
+                i += 1;
+            }
+        }
+        // 右段剩余的元素已经在正确的位置上
+        sources.extend(i..mid);
+
+        let merged: Vec<T> = sources.iter().map(|&src| self.slice[src].clone()).collect();
+        for (k, value) in (lo..).zip(merged) {
+            self.set(k, value);
+        }
+    }
+
+    fn quick(&mut self, mut lo: usize, mut hi: usize) {
+        while hi - lo > 1 {
+            if hi - lo == 2 {
+                if self.less(lo + 1, lo) {
+                    self.swap(lo, lo + 1);
+                }
+                return;
+            }
+
+            // 三数取中：把 lo、mid、hi - 1 排好序，中位数作为主元放到 hi - 1
+            let (mid, last) = (lo + (hi - lo) / 2, hi - 1);
+            if self.less(mid, lo) {
+                self.swap(mid, lo);
+            }
+            if self.less(last, mid) {
+                self.swap(last, mid);
+                if self.less(mid, lo) {
+                    self.swap(mid, lo);
+                }
+            }
+            self.swap(mid, last);
+
+            let mut store = lo;
+            for i in lo..last {
+                if self.less(i, last) {
+                    self.swap(i, store);
+                    store += 1;
+                }
+            }
+            self.swap(store, last);
+
+            // 递归处理较短的一侧，较长的一侧继续循环，栈深度为 O(log n)
+            if store - lo < hi - store - 1 {
+                self.quick(lo, store);
+                lo = store + 1;
+            } else {
+                self.quick(store + 1, hi);
+                hi = store;
+            }
+        }
+    }
+
+    fn heap(&mut self) {
+        let len = self.slice.len();
+        for i in (0..len / 2).rev() {
+            self.sift_down(i, len);
+        }
+        for end in (1..len).rev() {
+            self.swap(0, end);
+            self.sift_down(0, end);
+        }
+    }
+
+    /// 在 `[0, end)` 构成的大顶堆中下沉位置 `i` 的元素。
+    fn sift_down(&mut self, mut i: usize, end: usize) {
+        loop {
+            let left = 2 * i + 1;
+            let right = left + 1;
+            let mut top = i;
+
+            if left < end && self.less(top, left) {
+                top = left;
+            }
+            if right < end && self.less(top, right) {
+                top = right;
+            }
+            if top == i {
+                return;
+            }
+
+            self.swap(i, top);
+            i = top;
+        }
+    }
+
+    fn tim(&mut self) {
+        let len = self.slice.len();
+        // 栈中保存尚未归并的有序段 (起点, 长度)
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        let mut start = 0;
+
+        while start < len {
+            let mut end = self.natural_run(start);
+            if end - start < MIN_RUN {
+                let forced = (start + MIN_RUN).min(len);
+                self.insertion(start, forced, 1);
+                end = forced;
+            }
+            runs.push((start, end - start));
+            start = end;
+            self.collapse_runs(&mut runs, false);
+        }
+
+        self.collapse_runs(&mut runs, true);
+    }
+
+    /// 找出从 `start` 开始的自然有序段并返回其终点；严格递减的段会被就地反转。
+    fn natural_run(&mut self, start: usize) -> usize {
+        let len = self.slice.len();
+        let mut end = start + 1;
+        if end == len {
+            return end;
+        }
+
+        if self.less(end, end - 1) {
+            // 只接受严格递减，反转后才不会破坏稳定性
+            end += 1;
+            while end < len && self.less(end, end - 1) {
+                end += 1;
+            }
+            let (mut i, mut j) = (start, end - 1);
+            while i < j {
+                self.swap(i, j);
+                i += 1;
+                j -= 1;
+            }
+        } else {
+            end += 1;
+            while end < len && !self.less(end, end - 1) {
+                end += 1;
+            }
+        }
+        end
+    }
+
+    /// 维持栈上各段长度的不变式（`A > B + C` 且 `B > C`），`force` 为真时归并全部段。
+    fn collapse_runs(&mut self, runs: &mut Vec<(usize, usize)>, force: bool) {
+        while runs.len() > 1 {
+            let n = runs.len();
+            let c = runs[n - 1].1;
+            let b = runs[n - 2].1;
+            let a = if n > 2 { runs[n - 3].1 } else { usize::MAX };
+
+            let at = if force || a <= b + c {
+                // 与较短的相邻段归并
+                if a < c {
+                    n - 3
+                } else {
+                    n - 2
+                }
+            } else if b <= c {
+                n - 2
+            } else {
+                return;
+            };
+
+            let (lo, left) = runs[at];
+            let (mid, right) = runs[at + 1];
+            self.merge(lo, mid, mid + right);
+            runs[at] = (lo, left + right);
+            runs.remove(at + 1);
+        }
+    }
+}
+
+/// 在终端中以柱状图动画回放排序过程。
+///
+/// 每一步刷新一帧：正在比较的柱子为黄色，刚被移动的柱子为红色，
+/// 结束时所有柱子变为绿色。柱子的高度按数值在最小值和最大值之间线性缩放。
+///
+/// # 示例
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use vector_sort::comparator::DefaultOrder;
+/// use vector_sort::instrumented::{sort_traced, Algorithm, Animation};
+///
+/// let mut vec = vec![5, 3, 8, 1, 9, 2];
+/// let trace = sort_traced(&mut vec, Algorithm::Quick, DefaultOrder);
+///
+/// Animation::new()
+///     .delay(Duration::from_millis(100))
+///     .play(&trace, &mut std::io::stdout())
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Animation {
+    delay: Duration,
+    height: usize,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation {
+            delay: Duration::from_millis(30),
+            height: 16,
+        }
+    }
+}
+
+impl Animation {
+    /// 创建默认设置的动画：每帧 30 毫秒，柱子最高 16 行。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置每帧之间的间隔。
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// 设置柱子的最大高度（行数），至少为 1。
+    pub fn height(mut self, height: usize) -> Self {
+        self.height = height.max(1);
+        self
+    }
+
+    /// 把动画逐帧写入 `out`。
+    pub fn play<T, W>(&self, trace: &Trace<T>, out: &mut W) -> std::io::Result<()>
+    where
+        T: Clone + Into<f64>,
+        W: Write,
+    {
+        let mut state = trace.initial.clone();
+        let mut stats = Stats::default();
+
+        self.frame(out, trace.algorithm, &state, Highlight::None, &stats)?;
+        for step in &trace.steps {
+            match step {
+                Step::Compare { .. } => stats.comparisons += 1,
+                Step::Swap { .. } => stats.swaps += 1,
+                Step::Set { .. } => stats.writes += 1,
+            }
+            apply(&mut state, step);
+            self.frame(out, trace.algorithm, &state, Highlight::Step(step), &stats)?;
+            thread::sleep(self.delay);
+        }
+
+        self.frame(out, trace.algorithm, &state, Highlight::Done, &stats)
+    }
+
+    fn frame<T, W>(
+        &self,
+        out: &mut W,
+        algorithm: Algorithm,
+        state: &[T],
+        highlight: Highlight<'_, T>,
+        stats: &Stats,
+    ) -> std::io::Result<()>
+    where
+        T: Clone + Into<f64>,
+        W: Write,
+    {
+        let values: Vec<f64> = state.iter().map(|v| v.clone().into()).collect();
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+        // 最小值也至少显示一行
+        let heights: Vec<usize> = values
+            .iter()
+            .map(|v| 1 + ((v - min) / range * (self.height - 1) as f64).round() as usize)
+            .collect();
+
+        let colour = |index: usize| match highlight {
+            Highlight::Done => Colour::Green,
+            Highlight::Step(Step::Compare { i, j }) if index == *i || index == *j => Colour::Yellow,
+            Highlight::Step(Step::Swap { i, j }) if index == *i || index == *j => Colour::Red,
+            Highlight::Step(Step::Set { i, .. }) if index == *i => Colour::Red,
+            _ => Colour::Blue,
+        };
+
+        // 回到左上角并清屏
+        write!(out, "\x1b[H\x1b[2J")?;
+        writeln!(
+            out,
+            "{}  comparisons: {}  swaps: {}  writes: {}",
+            Style::new().bold().paint(algorithm.name()),
+            stats.comparisons,
+            stats.swaps,
+            stats.writes
+        )?;
+        for row in (1..=self.height).rev() {
+            for (index, &height) in heights.iter().enumerate() {
+                if height >= row {
+                    write!(out, "{}", colour(index).paint("█"))?;
+                } else {
+                    write!(out, " ")?;
+                }
+            }
+            writeln!(out)?;
+        }
+        out.flush()
+    }
+}
+
+/// 一帧中需要高亮的内容。
+#[derive(Clone, Copy)]
+enum Highlight<'a, T> {
+    None,
+    Step(&'a Step<T>),
+    /// 排序完成，全部高亮
+    Done,
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::DefaultOrder;
+    use rand::{thread_rng, Rng};
+
+    /// 测试所有算法在各种长度和重复程度下都能正确排序，且记录可以回放出相同结果
+    #[test]
+    fn all_algorithms_sort() {
+        let mut rng = thread_rng();
+        for algorithm in Algorithm::ALL {
+            for len in [0, 1, 2, 3, 10, 17, 64, 200] {
+                for max in [3, 1000] {
+                    let original: Vec<u32> = (0..len).map(|_| rng.gen_range(0..max)).collect();
+                    let mut expected = original.clone();
+                    expected.sort();
+
+                    let mut vec = original.clone();
+                    sort_counted(&mut vec, algorithm, DefaultOrder);
+                    assert_eq!(vec, expected, "{algorithm} on {original:?}");
+
+                    let mut vec = original.clone();
+                    let trace = sort_traced(&mut vec, algorithm, DefaultOrder);
+                    assert_eq!(vec, expected);
+                    assert_eq!(trace.initial, original);
+                    assert_eq!(trace.replay(), expected);
+                }
+            }
+        }
+    }
+
+    /// 测试稳定的算法保持相等元素的原有顺序
+    #[test]
+    fn stable_algorithms_are_stable() {
+        let mut rng = thread_rng();
+        let original: Vec<(u32, usize)> = (0..300).map(|i| (rng.gen_range(0..5), i)).collect();
+        let mut expected = original.clone();
+        expected.sort_by_key(|&(key, _)| key);
+
+        for algorithm in Algorithm::ALL.into_iter().filter(|a| a.is_stable()) {
+            let mut vec = original.clone();
+            sort_counted(&mut vec, algorithm, |a: &(u32, usize), b: &(u32, usize)| {
+                a.0.cmp(&b.0)
+            });
+            assert_eq!(vec, expected, "{algorithm}");
+        }
+    }
+
+    /// 测试计数与记录的步骤一致，以及已知输入下的计数
+    #[test]
+    fn counts_match_steps() {
+        let original: Vec<i32> = (0..50).rev().collect();
+        for algorithm in Algorithm::ALL {
+            let mut vec = original.clone();
+            let trace = sort_traced(&mut vec, algorithm, DefaultOrder);
+            let count =
+                |f: fn(&Step<i32>) -> bool| trace.steps.iter().filter(|s| f(s)).count() as u64;
+
+            assert_eq!(
+                trace.stats.comparisons,
+                count(|s| matches!(s, Step::Compare { .. }))
+            );
+            assert_eq!(trace.stats.swaps, count(|s| matches!(s, Step::Swap { .. })));
+            assert_eq!(trace.stats.writes, count(|s| matches!(s, Step::Set { .. })));
+
+            let mut vec = original.clone();
+            assert_eq!(sort_counted(&mut vec, algorithm, DefaultOrder), trace.stats);
+        }
+
+        // 已排序的输入：插入排序只需 n - 1 次比较，Timsort 识别为一个自然段
+        let mut sorted: Vec<i32> = (0..100).collect();
+        for algorithm in [Algorithm::Insertion, Algorithm::Tim] {
+            let stats = sort_counted(&mut sorted, algorithm, DefaultOrder);
+            assert_eq!(
+                stats,
+                Stats {
+                    comparisons: 99,
+                    swaps: 0,
+                    writes: 0
+                }
+            );
+        }
+        // 逆序的输入：插入排序每对元素都要交换一次
+        let mut reversed: Vec<i32> = (0..100).rev().collect();
+        let stats = sort_counted(&mut reversed, Algorithm::Insertion, DefaultOrder);
+        assert_eq!(stats.swaps, 100 * 99 / 2);
+    }
+
+    /// 测试算法名称的解析
+    #[test]
+    fn parse_algorithm() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.name().parse::<Algorithm>().unwrap(), algorithm);
+        }
+        assert_eq!("Quick".parse::<Algorithm>().unwrap(), Algorithm::Quick);
+        assert!("bogo".parse::<Algorithm>().is_err());
+    }
+
+    /// 测试动画输出包含每一帧
+    #[test]
+    fn animation_frames() {
+        let mut vec = vec![3, 1, 2];
+        let trace = sort_traced(&mut vec, Algorithm::Insertion, DefaultOrder);
+
+        let mut out = Vec::new();
+        Animation::new()
+            .delay(Duration::ZERO)
+            .height(4)
+            .play(&trace, &mut out)
+            .unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches("\x1b[2J").count(), trace.steps.len() + 2);
+        assert!(text.contains("comparisons: 3  swaps: 2  writes: 0"));
+    }
+}
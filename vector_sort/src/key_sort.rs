@@ -0,0 +1,225 @@
+//! 按代价较高或可能失败的键排序。
+//!
+//! 所有函数都先为每个元素计算一次键（Schwartzian 变换），再对下标排列排序，
+//! 最后把排列原地应用到切片上。键的计算全部成功之前切片不会被修改，
+//! 因此键提取失败时切片保持原样，而不是处于部分排序的状态。
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering::Relaxed},
+    Mutex,
+};
+
+use rayon::prelude::*;
+
+use crate::par_sort::par_sort_by;
+use crate::permutation::walk_cycles;
+
+/// 按可能失败的键稳定排序，每个元素的键恰好计算一次。
+///
+/// 按切片顺序计算键，遇到第一个错误立即返回，此时切片保持原样。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+/// * `f` - 键提取函数，返回 `Err` 表示该元素无法提取键。
+///
+/// # 返回值
+///
+/// 全部键提取成功时排序并返回 `Ok(())`，否则返回第一个错误。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::key_sort::try_sort_by_key;
+///
+/// let mut prices = vec!["12.5", "3", "7.25"];
+/// try_sort_by_key(&mut prices, |p| p.parse::<f64>().map(|x| (x * 100.0) as i64)).unwrap();
+/// assert_eq!(prices, ["3", "7.25", "12.5"]);
+///
+/// let mut bad = vec!["12.5", "n/a", "3"];
+/// assert!(try_sort_by_key(&mut bad, |p| p.parse::<f64>().map(|x| x as i64)).is_err());
+/// assert_eq!(bad, ["12.5", "n/a", "3"]);
+/// ```
+pub fn try_sort_by_key<T, K, E, F>(slice: &mut [T], f: F) -> Result<(), E>
+where
+    K: Ord,
+    F: FnMut(&T) -> Result<K, E>,
+{
+    let keys = slice.iter().map(f).collect::<Result<Vec<K>, E>>()?;
+
+    let mut perm: Vec<usize> = (0..slice.len()).collect();
+    perm.sort_by(|&a, &b| keys[a].cmp(&keys[b]));
+    walk_cycles(&mut perm, |a, b| slice.swap(a, b));
+    Ok(())
+}
+
+/// 并行计算键后稳定排序，每个元素的键恰好计算一次。
+///
+/// 适合键的计算代价远高于比较的场景（如解析、查表、规范化字符串），
+/// 键的计算和下标的排序都会并行进行。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::key_sort::par_sort_by_cached_key;
+///
+/// let mut words = vec!["Banana", "apple", "Cherry"];
+/// par_sort_by_cached_key(&mut words, |w| w.to_lowercase());
+/// assert_eq!(words, ["apple", "Banana", "Cherry"]);
+/// ```
+pub fn par_sort_by_cached_key<T, K, F>(slice: &mut [T], f: F)
+where
+    T: Sync,
+    K: Ord + Send + Sync,
+    F: Fn(&T) -> K + Sync,
+{
+    let keys: Vec<K> = slice.par_iter().map(&f).collect();
+    sort_by_keys(slice, &keys);
+}
+
+/// [`par_sort_by_cached_key`] 的可失败版本。
+///
+/// 键并行计算，某个元素失败后不再计算排在它之后的元素的键。
+/// 返回的总是切片中位置最靠前的错误，与串行的 [`try_sort_by_key`] 一致，不受线程调度影响；
+/// 出错时切片保持原样。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::key_sort::try_par_sort_by_cached_key;
+///
+/// let mut ids = vec!["id-30", "id-4", "id-x", "bad"];
+/// let result = try_par_sort_by_cached_key(&mut ids, |s| {
+///     s.strip_prefix("id-")
+///         .ok_or(format!("missing prefix: {s}"))?
+///         .parse::<u32>()
+///         .map_err(|e| format!("{s}: {e}"))
+/// });
+///
+/// assert_eq!(result.unwrap_err(), "id-x: invalid digit found in string");
+/// assert_eq!(ids, ["id-30", "id-4", "id-x", "bad"]);
+/// ```
+pub fn try_par_sort_by_cached_key<T, K, E, F>(slice: &mut [T], f: F) -> Result<(), E>
+where
+    T: Sync,
+    K: Ord + Send + Sync,
+    E: Send,
+    F: Fn(&T) -> Result<K, E> + Sync,
+{
+    // 目前已知最靠前的出错位置，之后的元素无需再计算
+    let first_error = AtomicUsize::new(usize::MAX);
+    let error: Mutex<Option<(usize, E)>> = Mutex::new(None);
+
+    let keys: Vec<Option<K>> = slice
+        .par_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            if i > first_error.load(Relaxed) {
+                return None;
+            }
+            match f(item) {
+                Ok(key) => Some(key),
+                Err(e) => {
+                    first_error.fetch_min(i, Relaxed);
+                    let mut error = error.lock().unwrap_or_else(|e| e.into_inner());
+                    if error.as_ref().is_none_or(|&(j, _)| i < j) {
+                        *error = Some((i, e));
+                    }
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if let Some((_, e)) = error.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        return Err(e);
+    }
+
+    let keys: Vec<K> = keys.into_iter().map(|key| key.expect("no error")).collect();
+    sort_by_keys(slice, &keys);
+    Ok(())
+}
+
+/// 按预先计算好的键并行地稳定排序，`keys[i]` 是 `slice[i]` 的键。
+fn sort_by_keys<T, K: Ord + Sync>(slice: &mut [T], keys: &[K]) {
+    let mut perm: Vec<usize> = (0..slice.len()).collect();
+    par_sort_by(&mut perm, |&a: &usize, &b: &usize| keys[a].cmp(&keys[b]));
+    walk_cycles(&mut perm, |a, b| slice.swap(a, b));
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::par_sort::PAR_SORT_THRESHOLD;
+    use rand::{thread_rng, Rng};
+
+    fn random_pairs(len: usize) -> Vec<(u32, usize)> {
+        let mut rng = thread_rng();
+        (0..len).map(|i| (rng.gen_range(0..50), i)).collect()
+    }
+
+    /// 测试三种排序都与标准库稳定排序一致，且每个键只计算一次
+    #[test]
+    fn matches_stable_sort_and_computes_keys_once() {
+        for len in [0, 1, 2, 100, PAR_SORT_THRESHOLD + 1] {
+            let original = random_pairs(len);
+            let mut expected = original.clone();
+            expected.sort_by_key(|&(key, _)| key);
+
+            let calls = AtomicUsize::new(0);
+            let key = |&(k, _): &(u32, usize)| {
+                calls.fetch_add(1, Relaxed);
+                k
+            };
+
+            let mut vec = original.clone();
+            try_sort_by_key(&mut vec, |x| Ok::<_, ()>(key(x))).unwrap();
+            assert_eq!(vec, expected);
+
+            let mut vec = original.clone();
+            par_sort_by_cached_key(&mut vec, key);
+            assert_eq!(vec, expected);
+
+            let mut vec = original.clone();
+            try_par_sort_by_cached_key(&mut vec, |x| Ok::<_, ()>(key(x))).unwrap();
+            assert_eq!(vec, expected);
+
+            assert_eq!(calls.load(Relaxed), 3 * len);
+        }
+    }
+
+    /// 测试出错时返回位置最靠前的错误，且切片保持原样
+    #[test]
+    fn first_error_leaves_slice_untouched() {
+        let original: Vec<u32> = (0..20_000).rev().collect();
+        let key = |&x: &u32| if x % 997 == 3 { Err(x) } else { Ok(x) };
+        // 逆序排列中最靠前的出错元素
+        let first = *original.iter().find(|&&x| x % 997 == 3).unwrap();
+
+        let mut vec = original.clone();
+        assert_eq!(try_sort_by_key(&mut vec, key), Err(first));
+        assert_eq!(vec, original);
+
+        for _ in 0..20 {
+            let mut vec = original.clone();
+            assert_eq!(try_par_sort_by_cached_key(&mut vec, key), Err(first));
+            assert_eq!(vec, original);
+        }
+    }
+
+    /// 测试串行版本遇到错误后不再计算后面的键
+    #[test]
+    fn serial_stops_at_first_error() {
+        let mut calls = 0;
+        let mut vec = vec![3, 1, -1, 2, -2];
+        let result = try_sort_by_key(&mut vec, |&x: &i32| {
+            calls += 1;
+            u32::try_from(x)
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls, 3);
+        assert_eq!(vec, [3, 1, -1, 2, -2]);
+    }
+}
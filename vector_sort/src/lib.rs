@@ -0,0 +1,14 @@
+pub mod collation;
+pub mod comparator;
+pub mod external_sort;
+pub mod float_sort;
+mod heap;
+pub mod instrumented;
+pub mod key_sort;
+pub mod par_sort;
+pub mod permutation;
+pub mod radix_sort;
+pub mod record_sort;
+pub mod select;
+pub mod sorted;
+pub mod vec_sort;
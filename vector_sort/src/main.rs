@@ -1,11 +1,182 @@
-use vec_sort::{sort_float_vector, sort_integer_vector, sort_struct_vector};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
 
-mod vec_sort;
+use anyhow::{Context, Result};
+use clap::{builder::PathBufValueParser, Arg, ArgAction, Command};
+use rand::seq::SliceRandom;
+use vector_sort::comparator::DefaultOrder;
+use vector_sort::instrumented::{sort_traced, Algorithm, Animation};
+use vector_sort::record_sort::{Format, Records, SortKey};
+use vector_sort::vec_sort::{sort_float_vector, sort_integer_vector, sort_struct_vector};
 
-fn main() {
-    sort_integer_vector();
+fn cli() -> Command {
+    Command::new("vector_sort")
+        .version("0.1.0")
+        .about("Sorts CSV or JSON Lines records by one or more typed columns")
+        .arg(
+            Arg::new("files")
+                .help("Input files; reads stdin when omitted or when a file is `-`")
+                .value_parser(PathBufValueParser::default())
+                .num_args(0..),
+        )
+        .arg(
+            Arg::new("key")
+                .short('k')
+                .long("key")
+                .help("Sort key as COLUMN[:asc|desc][:auto|num|date|str], may be repeated")
+                .action(ArgAction::Append)
+                .value_parser(clap::value_parser!(String))
+                .required_unless_present_any(["demo", "visualize", "trace"]),
+        )
+        .arg(
+            Arg::new("format")
+                .short('f')
+                .long("format")
+                .help("Input format: csv or jsonl [default: by file extension, else csv]"),
+        )
+        .arg(
+            Arg::new("output-format")
+                .long("output-format")
+                .help("Output format: csv or jsonl [default: same as input]"),
+        )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .help("Write the result to this file instead of stdout")
+                .value_parser(PathBufValueParser::default()),
+        )
+        .arg(
+            Arg::new("unstable")
+                .long("unstable")
+                .help("Use an unstable sort; records with equal keys may be reordered")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("demo")
+                .long("demo")
+                .help("Run the built-in vector sorting examples and exit")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("visualize")
+                .long("visualize")
+                .value_name("ALGORITHM")
+                .help("Animate a sorting algorithm on random data: insertion, merge, quick, heap, shell or tim")
+                .conflicts_with("trace"),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .value_name("ALGORITHM")
+                .help("Print a JSON step-by-step trace of a sorting algorithm on random data"),
+        )
+        .arg(
+            Arg::new("size")
+                .long("size")
+                .help("Number of random values for --visualize and --trace")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("32"),
+        )
+}
+
+/// 生成 1..=size 的随机排列，记录每一步后动画回放或输出 JSON。
+fn run_instrumented(algorithm: &str, size: u32, animate: bool) -> Result<()> {
+    let algorithm: Algorithm = algorithm.parse()?;
+    let mut values: Vec<u32> = (1..=size).collect();
+    values.shuffle(&mut rand::thread_rng());
+
+    let trace = sort_traced(&mut values, algorithm, DefaultOrder);
+    let mut out = io::stdout().lock();
+    if animate {
+        Animation::new().play(&trace, &mut out)?;
+    } else {
+        serde_json::to_writer(&mut out, &trace)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    let matches = cli().get_matches();
+
+    if matches.get_flag("demo") {
+        sort_integer_vector();
+
+        sort_float_vector();
+
+        sort_struct_vector();
+
+        return Ok(());
+    }
+
+    let size = *matches.get_one::<u32>("size").expect("has default");
+    if let Some(algorithm) = matches.get_one::<String>("visualize") {
+        return run_instrumented(algorithm, size, true);
+    }
+    if let Some(algorithm) = matches.get_one::<String>("trace") {
+        return run_instrumented(algorithm, size, false);
+    }
+
+    let keys = matches
+        .get_many::<String>("key")
+        .unwrap_or_default()
+        .map(|k| k.parse())
+        .collect::<Result<Vec<SortKey>>>()?;
+    let format: Option<Format> = matches
+        .get_one::<String>("format")
+        .map(|f| f.parse())
+        .transpose()?;
+
+    let mut files: Vec<PathBuf> = matches
+        .get_many::<PathBuf>("files")
+        .unwrap_or_default()
+        .cloned()
+        .collect();
+    if files.is_empty() {
+        files.push(PathBuf::from("-"));
+    }
+
+    let mut records: Option<Records> = None;
+    for path in &files {
+        let input_format = format
+            .or_else(|| Format::from_path(path))
+            .unwrap_or(Format::Csv);
+
+        let batch = if path.as_os_str() == "-" {
+            Records::read(io::stdin().lock(), input_format)
+        } else {
+            let file = File::open(path).with_context(|| format!("cannot open {:?}", path))?;
+            Records::read(file, input_format)
+        }
+        .with_context(|| format!("cannot read {:?}", path))?;
+
+        match records.as_mut() {
+            Some(records) => records.append(batch)?,
+            None => records = Some(batch),
+        }
+    }
+    let mut records = records.expect("at least one input");
+
+    records.sort(&keys, !matches.get_flag("unstable"))?;
+
+    let output_format = matches
+        .get_one::<String>("output-format")
+        .map(|f| f.parse())
+        .transpose()?
+        .unwrap_or(records.format());
 
-    sort_float_vector();
+    match matches.get_one::<PathBuf>("output") {
+        Some(path) => {
+            let file = File::create(path).with_context(|| format!("cannot create {:?}", path))?;
+            records.write(BufWriter::new(file), output_format)?;
+        }
+        None => records.write(io::stdout().lock(), output_format)?,
+    }
 
-    sort_struct_vector();
+    Ok(())
 }
@@ -0,0 +1,357 @@
+use std::{mem::MaybeUninit, ptr};
+
+use rayon::prelude::*;
+
+use crate::comparator::Compare;
+
+/// 并行排序的默认阈值：元素个数不超过该值时直接使用串行排序。
+///
+/// 对于较小的输入，线程调度的开销会超过并行带来的收益。
+pub const PAR_SORT_THRESHOLD: usize = 1 << 13;
+
+/// 并行稳定排序，元素个数不超过 [`PAR_SORT_THRESHOLD`] 时退化为串行 `sort_by`。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+/// * `cmp` - 比较器，可以是 `sort_by` 形式的闭包或 [`Comparator`](crate::comparator::Comparator)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::par_sort::par_sort_by;
+///
+/// let mut vec: Vec<i32> = (0..100_000).rev().collect();
+/// par_sort_by(&mut vec, |a: &i32, b: &i32| a.cmp(b));
+///
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn par_sort_by<T, C>(slice: &mut [T], cmp: C)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    par_sort_by_with_threshold(slice, &cmp, PAR_SORT_THRESHOLD);
+}
+
+/// 与 [`par_sort_by`] 相同，但可以指定串行回退的阈值。
+pub fn par_sort_by_with_threshold<T, C>(slice: &mut [T], cmp: &C, threshold: usize)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    if slice.len() <= threshold.max(1) {
+        slice.sort_by(|a, b| cmp.compare(a, b));
+    } else {
+        par_merge_sort_by(slice, cmp, threshold);
+    }
+}
+
+/// 并行不稳定排序，元素个数不超过 [`PAR_SORT_THRESHOLD`] 时退化为串行 `sort_unstable_by`。
+pub fn par_sort_unstable_by<T, C>(slice: &mut [T], cmp: C)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    if slice.len() <= PAR_SORT_THRESHOLD {
+        slice.sort_unstable_by(|a, b| cmp.compare(a, b));
+    } else {
+        slice.par_sort_unstable_by(|a, b| cmp.compare(a, b));
+    }
+}
+
+/// 并行归并排序（稳定）。
+///
+/// 使用 `rayon::join` 递归地将切片一分为二并行排序，子问题规模不超过 `threshold` 时串行排序。
+/// 两个有序的半区再并行归并：较长的一段取中点，在另一段中二分查找切分位置，
+/// 得到两个可以并行执行的子归并。整个排序只分配一次与输入等长的辅助空间。
+///
+/// 比较器 panic 时切片仍是输入的一个排列：归并先把结果写入辅助空间，
+/// 全部比较完成后才把元素移回切片。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+/// * `cmp` - 比较器。
+/// * `threshold` - 串行排序的子问题规模上限。
+pub fn par_merge_sort_by<T, C>(slice: &mut [T], cmp: &C, threshold: usize)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    let threshold = threshold.max(1);
+    if slice.len() <= threshold {
+        slice.sort_by(|a, b| cmp.compare(a, b));
+        return;
+    }
+
+    let mut scratch: Vec<MaybeUninit<T>> = Vec::with_capacity(slice.len());
+    scratch.resize_with(slice.len(), MaybeUninit::uninit);
+    merge_sort(slice, &mut scratch, cmp, threshold);
+}
+
+fn merge_sort<T, C>(slice: &mut [T], scratch: &mut [MaybeUninit<T>], cmp: &C, threshold: usize)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    if slice.len() <= threshold {
+        slice.sort_by(|a, b| cmp.compare(a, b));
+        return;
+    }
+
+    let mid = slice.len() / 2;
+    let (left, right) = slice.split_at_mut(mid);
+    let (left_scratch, right_scratch) = scratch.split_at_mut(mid);
+    rayon::join(
+        || merge_sort(left, left_scratch, cmp, threshold),
+        || merge_sort(right, right_scratch, cmp, threshold),
+    );
+
+    par_merge(left, right, scratch, cmp);
+
+    // 所有比较都已完成，把归并结果移回切片
+    slice
+        .par_chunks_mut(MERGE_GRAIN)
+        .zip(scratch.par_chunks_mut(MERGE_GRAIN))
+        .for_each(|(dst, src)| {
+            // SAFETY: `par_merge` 已经把切片中每个元素恰好移动一次到 `scratch` 中，
+            // 这里按位移回，切片中原有的值不会被释放，`scratch` 本身也不会释放元素
+            unsafe {
+                ptr::copy_nonoverlapping(src.as_ptr().cast::<T>(), dst.as_mut_ptr(), dst.len())
+            }
+        });
+}
+
+/// 串行归并的规模上限，也是移回元素时每个并行任务处理的元素数。
+const MERGE_GRAIN: usize = 1 << 12;
+
+/// 把有序的 `left` 与 `right` 稳定地归并到 `dest` 中，相等时 `left` 的元素在前。
+///
+/// 元素被按位复制到 `dest`，`left` 与 `right` 不会被修改，仍然拥有这些元素。
+/// 较长的一段取中点，在另一段中二分查找对应的位置，把问题分成两个独立的子归并并行执行。
+fn par_merge<T, C>(left: &mut [T], right: &mut [T], dest: &mut [MaybeUninit<T>], cmp: &C)
+where
+    T: Send,
+    C: Compare<T> + Sync,
+{
+    debug_assert_eq!(left.len() + right.len(), dest.len());
+    if dest.len() <= MERGE_GRAIN {
+        serial_merge(left, right, dest, cmp);
+        return;
+    }
+
+    let (left_mid, right_mid) = if left.len() >= right.len() {
+        // `right` 中严格小于 `left[mid]` 的元素排在它前面
+        let mid = left.len() / 2;
+        let pivot = &left[mid];
+        (
+            mid,
+            right.partition_point(|x| cmp.compare(x, pivot).is_lt()),
+        )
+    } else {
+        // `left` 中不大于 `right[mid]` 的元素排在它前面
+        let mid = right.len() / 2;
+        let pivot = &right[mid];
+        (left.partition_point(|x| cmp.compare(x, pivot).is_le()), mid)
+    };
+
+    let (left_lo, left_hi) = left.split_at_mut(left_mid);
+    let (right_lo, right_hi) = right.split_at_mut(right_mid);
+    let (dest_lo, dest_hi) = dest.split_at_mut(left_mid + right_mid);
+    rayon::join(
+        || par_merge(left_lo, right_lo, dest_lo, cmp),
+        || par_merge(left_hi, right_hi, dest_hi, cmp),
+    );
+}
+
+fn serial_merge<T, C>(left: &[T], right: &[T], dest: &mut [MaybeUninit<T>], cmp: &C)
+where
+    C: Compare<T>,
+{
+    let (mut i, mut j) = (0, 0);
+    for slot in dest.iter_mut() {
+        // 右段元素严格更小时才先取它，保证稳定
+        let take_right =
+            i == left.len() || (j < right.len() && cmp.compare(&right[j], &left[i]).is_lt());
+        let src = if take_right {
+            j += 1;
+            &right[j - 1]
+        } else {
+            i += 1;
+            &left[i - 1]
+        };
+        // SAFETY: 每个元素只被复制一次，复制出的值在 `MaybeUninit` 中不会被释放；
+        // 原来的值仍由切片拥有，直到调用者把归并结果移回切片
+        slot.write(unsafe { ptr::read(src) });
+    }
+}
+
+/// 并行采样排序（稳定）。
+///
+/// 1. 从输入中等间隔采样并排序，选出 `buckets - 1` 个分隔元素；
+/// 2. 并行地为每个元素计算所属的桶，再在原地把元素按桶重新排列，同一个桶内保持原有顺序；
+/// 3. 并行地对每个桶所在的区间做稳定排序。
+///
+/// 元素分配到桶时保持原有的相对顺序，因此整体排序是稳定的。
+/// 大量重复元素会集中在同一个桶中，此时并行度会下降，但结果依然正确。
+/// 元素始终留在 `vec` 中，比较器 panic 时 `vec` 仍是输入的一个排列。
+///
+/// # 参数
+///
+/// * `vec` - 一个可变引用，指向要排序的向量。
+/// * `cmp` - 比较器。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::par_sort::par_sample_sort_by;
+///
+/// let mut vec: Vec<u64> = (0..50_000).map(|i| (i * 7919) % 1000).collect();
+/// par_sample_sort_by(&mut vec, |a: &u64, b: &u64| a.cmp(b));
+///
+/// assert!(vec.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn par_sample_sort_by<T, C>(vec: &mut Vec<T>, cmp: C)
+where
+    T: Send + Sync,
+    C: Compare<T> + Sync,
+{
+    if vec.len() <= PAR_SORT_THRESHOLD {
+        vec.sort_by(|a, b| cmp.compare(a, b));
+        return;
+    }
+
+    // 桶的数量取线程数的若干倍，以便在数据分布不均时仍能较好地负载均衡
+    let buckets = rayon::current_num_threads() * 4;
+    let splitters = choose_splitters(vec, &cmp, buckets);
+
+    // 并行计算每个元素所在的桶：第一个大于该元素的分隔元素的下标
+    let bucket_of: Vec<usize> = vec
+        .par_iter()
+        .map(|x| splitters.partition_point(|&s| cmp.compare(&vec[s], x).is_le()))
+        .collect();
+
+    let mut counts = vec![0; splitters.len() + 1];
+    for &b in &bucket_of {
+        counts[b] += 1;
+    }
+
+    // 每个元素的目标位置：所在桶的起点加上它在桶内的序号
+    let mut next: Vec<usize> = counts
+        .iter()
+        .scan(0, |start, &c| {
+            let first = *start;
+            *start += c;
+            Some(first)
+        })
+        .collect();
+    let mut target: Vec<usize> = bucket_of
+        .iter()
+        .map(|&b| {
+            next[b] += 1;
+            next[b] - 1
+        })
+        .collect();
+
+    // 沿置换的环原地交换，不调用比较器
+    for i in 0..vec.len() {
+        while target[i] != i {
+            let t = target[i];
+            vec.swap(i, t);
+            target.swap(i, t);
+        }
+    }
+
+    let mut ranges = Vec::with_capacity(counts.len());
+    let mut rest = vec.as_mut_slice();
+    for &c in &counts {
+        let (bucket, tail) = rest.split_at_mut(c);
+        ranges.push(bucket);
+        rest = tail;
+    }
+    ranges
+        .into_par_iter()
+        .for_each(|bucket| bucket.sort_by(|a, b| cmp.compare(a, b)));
+}
+
+/// 等间隔采样并返回有序的分隔元素下标，下标指向 `vec` 中的元素。
+fn choose_splitters<T, C>(vec: &[T], cmp: &C, buckets: usize) -> Vec<usize>
+where
+    C: Compare<T>,
+{
+    const OVERSAMPLE: usize = 8;
+
+    let sample_len = (buckets * OVERSAMPLE).min(vec.len());
+    let step = vec.len() / sample_len;
+    let mut sample: Vec<usize> = (0..sample_len).map(|i| i * step).collect();
+    sample.sort_by(|&a, &b| cmp.compare(&vec[a], &vec[b]));
+
+    let mut splitters: Vec<usize> = (1..buckets)
+        .map(|i| sample[i * sample_len / buckets])
+        .collect();
+    // 相等的分隔元素只会产生空桶，去掉它们
+    splitters.dedup_by(|a, b| cmp.compare(&vec[*a], &vec[*b]).is_eq());
+    splitters
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::{by, DefaultOrder};
+    use rand::{thread_rng, Rng};
+
+    fn random_vec(len: usize, max: u32) -> Vec<u32> {
+        let mut rng = thread_rng();
+        (0..len).map(|_| rng.gen_range(0..max)).collect()
+    }
+
+    /// 测试各个并行排序与标准库排序结果一致
+    #[test]
+    fn matches_std_sort() {
+        for &len in &[0, 1, 2, 100, PAR_SORT_THRESHOLD + 1, 100_000] {
+            for &max in &[4, 1_000_000] {
+                let original = random_vec(len, max);
+                let mut expected = original.clone();
+                expected.sort();
+
+                let mut merge = original.clone();
+                par_merge_sort_by(&mut merge, &DefaultOrder, 64);
+                assert_eq!(merge, expected);
+
+                let mut sample = original.clone();
+                par_sample_sort_by(&mut sample, DefaultOrder);
+                assert_eq!(sample, expected);
+
+                let mut stable = original.clone();
+                par_sort_by(&mut stable, DefaultOrder);
+                assert_eq!(stable, expected);
+
+                let mut unstable = original.clone();
+                par_sort_unstable_by(&mut unstable, |a: &u32, b: &u32| a.cmp(b));
+                assert_eq!(unstable, expected);
+            }
+        }
+    }
+
+    /// 测试并行归并排序与采样排序的稳定性
+    #[test]
+    fn stability() {
+        let original: Vec<(u32, usize)> = random_vec(50_000, 16)
+            .into_iter()
+            .enumerate()
+            .map(|(i, k)| (k, i))
+            .collect();
+        let mut expected = original.clone();
+        expected.sort_by_key(|&(k, _)| k);
+
+        let mut merge = original.clone();
+        par_merge_sort_by(&mut merge, &by(|&(k, _): &(u32, usize)| k), 128);
+        assert_eq!(merge, expected);
+
+        let mut sample = original.clone();
+        par_sample_sort_by(&mut sample, by(|&(k, _): &(u32, usize)| k));
+        assert_eq!(sample, expected);
+    }
+}
@@ -0,0 +1,298 @@
+//! 按下标排列（argsort）排序，适用于按列存储的数据。
+//!
+//! 当数据以多个等长的列（如 `names: Vec<String>`、`ages: Vec<u32>`）保存，
+//! 而不是 `Vec<Person>` 时，可以先对某一列求出排序后的下标排列，
+//! 再把同一个排列原地应用到所有列上，无需组装中间结构体。
+
+use crate::comparator::Compare;
+
+/// 可以按下标交换元素的序列，[`apply_permutation`] 通过它同时重排多个不同类型的列。
+///
+/// 已为 `Vec<T>`、数组和 `&mut [T]` 实现；对切片引用取 `&mut` 即可作为一列传入。
+pub trait Permutable {
+    /// 元素个数。
+    fn len(&self) -> usize;
+
+    /// 是否为空。
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 交换两个位置上的元素。
+    fn swap(&mut self, a: usize, b: usize);
+}
+
+impl<T> Permutable for Vec<T> {
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b)
+    }
+}
+
+impl<T, const N: usize> Permutable for [T; N] {
+    fn len(&self) -> usize {
+        N
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.as_mut_slice().swap(a, b)
+    }
+}
+
+impl<T> Permutable for &mut [T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        <[T]>::swap(self, a, b)
+    }
+}
+
+/// 返回使切片有序的下标排列：`perm[i]` 是排序后第 `i` 个元素在原切片中的下标。
+///
+/// 排序是稳定的，相等元素的下标保持升序。原切片不会被修改。
+///
+/// # 参数
+///
+/// * `slice` - 要排序的数据。
+/// * `cmp` - 比较器，可以是 `sort_by` 形式的闭包或 [`Comparator`](crate::comparator::Comparator)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::comparator::DefaultOrder;
+/// use vector_sort::permutation::argsort;
+///
+/// let ages = vec![25, 60, 1, 25];
+/// assert_eq!(argsort(&ages, DefaultOrder), [2, 0, 3, 1]);
+/// ```
+pub fn argsort<T, C: Compare<T>>(slice: &[T], cmp: C) -> Vec<usize> {
+    let mut perm: Vec<usize> = (0..slice.len()).collect();
+    perm.sort_by(|&a, &b| cmp.compare(&slice[a], &slice[b]));
+    perm
+}
+
+/// 按提取出的键求下标排列，键只在比较时借用，参见 [`argsort`]。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::permutation::argsort_by_key;
+///
+/// let names = vec!["Zoe", "al", "John"];
+/// assert_eq!(argsort_by_key(&names, |n| n.to_lowercase()), [1, 2, 0]);
+/// ```
+pub fn argsort_by_key<T, K, F>(slice: &[T], mut f: F) -> Vec<usize>
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    let mut perm: Vec<usize> = (0..slice.len()).collect();
+    perm.sort_by_key(|&i| f(&slice[i]));
+    perm
+}
+
+/// 把同一个下标排列原地应用到多个列上：每一列的第 `i` 个元素变为原来的第 `perm[i]` 个元素。
+///
+/// 沿着置换环交换元素，时间复杂度 O(n × 列数)，额外空间为一份排列的拷贝。
+///
+/// # Panics
+///
+/// `perm` 不是 `0..n` 的一个排列，或者某一列的长度不等于 `perm.len()` 时 panic，
+/// 此时所有列都不会被修改。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::permutation::apply_permutation;
+///
+/// let mut names = vec!["Zoe", "Al", "John"];
+/// let mut ages = vec![25, 60, 1];
+/// apply_permutation(&[1, 2, 0], &mut [&mut names, &mut ages]);
+///
+/// assert_eq!(names, ["Al", "John", "Zoe"]);
+/// assert_eq!(ages, [60, 1, 25]);
+/// ```
+pub fn apply_permutation(perm: &[usize], columns: &mut [&mut dyn Permutable]) {
+    assert!(
+        is_permutation(perm),
+        "apply_permutation: not a permutation of 0..{}",
+        perm.len()
+    );
+    for (index, column) in columns.iter().enumerate() {
+        assert_eq!(
+            column.len(),
+            perm.len(),
+            "apply_permutation: column {index} has a different length than the permutation"
+        );
+    }
+
+    let mut perm = perm.to_vec();
+    walk_cycles(&mut perm, |a, b| {
+        for column in columns.iter_mut() {
+            column.swap(a, b);
+        }
+    });
+}
+
+/// 按 `keys` 排序，并让 `values` 中的每一列跟随 `keys` 做相同的重排。排序是稳定的。
+///
+/// # Panics
+///
+/// 某一列的长度与 `keys` 不同时 panic，此时所有数据都不会被修改。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::comparator::by_desc;
+/// use vector_sort::permutation::sort_together;
+///
+/// let mut ages = vec![25, 60, 1];
+/// let mut names = vec!["Zoe", "Al", "John"];
+/// let mut ids = vec![7, 8, 9];
+///
+/// // 按年龄降序，名字和编号随之移动
+/// sort_together(&mut ages, &mut [&mut names, &mut ids], by_desc(|&age: &u32| age));
+///
+/// assert_eq!(ages, [60, 25, 1]);
+/// assert_eq!(names, ["Al", "Zoe", "John"]);
+/// assert_eq!(ids, [8, 7, 9]);
+/// ```
+pub fn sort_together<K, C: Compare<K>>(keys: &mut [K], values: &mut [&mut dyn Permutable], cmp: C) {
+    for (index, column) in values.iter().enumerate() {
+        assert_eq!(
+            column.len(),
+            keys.len(),
+            "sort_together: column {index} has a different length than the keys"
+        );
+    }
+
+    let mut perm = argsort(keys, cmp);
+    walk_cycles(&mut perm, |a, b| {
+        keys.swap(a, b);
+        for column in values.iter_mut() {
+            column.swap(a, b);
+        }
+    });
+}
+
+/// 判断 `perm` 是否恰好包含 `0..perm.len()` 中的每个下标一次。
+pub fn is_permutation(perm: &[usize]) -> bool {
+    let mut seen = vec![false; perm.len()];
+    perm.iter()
+        .all(|&i| i < seen.len() && !std::mem::replace(&mut seen[i], true))
+}
+
+/// 沿着置换环调用 `swap`，使得按顺序执行这些交换后第 `i` 个位置得到原来的第 `perm[i]` 个元素。
+///
+/// `perm` 必须是合法的排列，会在过程中被修改。
+pub(crate) fn walk_cycles<F>(perm: &mut [usize], mut swap: F)
+where
+    F: FnMut(usize, usize),
+{
+    for start in 0..perm.len() {
+        let mut current = start;
+        while perm[current] != start {
+            let next = perm[current];
+            swap(current, next);
+            perm[current] = current;
+            current = next;
+        }
+        perm[current] = current;
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::{by, DefaultOrder};
+    use rand::{seq::SliceRandom, thread_rng, Rng};
+
+    /// 测试 argsort 与对 (值, 下标) 排序的结果一致
+    #[test]
+    fn argsort_is_stable() {
+        let mut rng = thread_rng();
+        for len in 0..100 {
+            let values: Vec<u32> = (0..len).map(|_| rng.gen_range(0..10)).collect();
+            let perm = argsort(&values, DefaultOrder);
+
+            let mut expected: Vec<(u32, usize)> = values.iter().copied().zip(0..).collect();
+            expected.sort();
+            assert_eq!(perm, expected.iter().map(|&(_, i)| i).collect::<Vec<_>>());
+            assert_eq!(perm, argsort_by_key(&values, |&v| v));
+        }
+    }
+
+    /// 测试排列应用到多列后与逐个取值一致
+    #[test]
+    fn apply_to_columns() {
+        let mut rng = thread_rng();
+        for len in 0..50 {
+            let mut perm: Vec<usize> = (0..len).collect();
+            perm.shuffle(&mut rng);
+
+            let letters: Vec<String> = (0..len).map(|i| format!("v{i}")).collect();
+            let numbers: Vec<u64> = (0..len as u64).map(|i| i * i).collect();
+            let (mut a, mut b) = (letters.clone(), numbers.clone());
+            let mut slice = b.as_mut_slice();
+            apply_permutation(&perm, &mut [&mut a, &mut slice]);
+
+            for (i, &p) in perm.iter().enumerate() {
+                assert_eq!(a[i], letters[p]);
+                assert_eq!(b[i], numbers[p]);
+            }
+        }
+    }
+
+    /// 测试非法排列和长度不一致时 panic 且不修改数据
+    #[test]
+    fn invalid_input_panics() {
+        assert!(is_permutation(&[2, 0, 1]));
+        assert!(!is_permutation(&[0, 0, 1]));
+        assert!(!is_permutation(&[0, 3, 1]));
+
+        let mut column = vec![1, 2, 3];
+        let result = std::panic::catch_unwind(move || {
+            apply_permutation(&[0, 0, 1], &mut [&mut column]);
+        });
+        assert!(result.is_err());
+
+        let mut keys = vec![3, 1, 2];
+        let mut short = vec!["a", "b"];
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            sort_together(&mut keys, &mut [&mut short], DefaultOrder);
+        }));
+        assert!(result.is_err());
+        assert_eq!(keys, [3, 1, 2]);
+        assert_eq!(short, ["a", "b"]);
+    }
+
+    /// 测试多列跟随键排序，结果与先组装结构体再稳定排序一致
+    #[test]
+    fn sort_together_matches_struct_sort() {
+        let mut rng = thread_rng();
+        let len = 200;
+        let mut ages: Vec<u32> = (0..len).map(|_| rng.gen_range(0..20)).collect();
+        let mut ids: Vec<usize> = (0..len).collect();
+        let mut names: Vec<String> = ids.iter().map(|i| format!("person{i}")).collect();
+
+        let mut rows: Vec<(u32, usize, String)> = ages
+            .iter()
+            .zip(&ids)
+            .zip(&names)
+            .map(|((&a, &i), n)| (a, i, n.clone()))
+            .collect();
+        rows.sort_by_key(|&(age, _, _)| age);
+
+        sort_together(&mut ages, &mut [&mut ids, &mut names], by(|&age: &u32| age));
+
+        for (k, (age, id, name)) in rows.into_iter().enumerate() {
+            assert_eq!((ages[k], ids[k], &names[k]), (age, id, &name));
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use std::cmp::Ordering;
+
+use crate::permutation::walk_cycles;
+
+/// 元素个数不超过该值时，[`radix_sort`] 直接使用比较排序。
+pub const RADIX_THRESHOLD: usize = 256;
+
+/// MSD 基数排序中，桶内元素个数不超过该值时改用比较排序。
+const MSD_CUTOFF: usize = 64;
+
+/// 可以按字节做基数排序的固定宽度键。
+///
+/// 实现时需要把值映射为一个无符号整数，使其无符号顺序与原类型的顺序一致：
+/// - 无符号整数保持不变；
+/// - 有符号整数翻转符号位；
+/// - 浮点数若为负则翻转所有位，否则只翻转符号位，得到的顺序即 IEEE 754 totalOrder，
+///   与 [`sort_floats_total`](crate::float_sort::sort_floats_total) 一致。
+pub trait RadixKey: Copy {
+    /// 键的字节数，即基数排序需要的最多趟数。
+    const BYTES: usize;
+
+    /// 返回映射后第 `i` 个字节（0 为最低字节）。
+    fn radix_byte(&self, i: usize) -> u8;
+
+    /// 按映射后的无符号顺序比较，用于小规模时的回退排序。
+    fn radix_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_radix_key {
+    ($($t:ty => $u:ty, |$x:ident| $ordered:expr;)*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+
+                fn radix_byte(&self, i: usize) -> u8 {
+                    let $x = *self;
+                    let ordered: $u = $ordered;
+                    (ordered >> (8 * i)) as u8
+                }
+
+                fn radix_cmp(&self, other: &Self) -> Ordering {
+                    let ordered = |$x: $t| -> $u { $ordered };
+                    ordered(*self).cmp(&ordered(*other))
+                }
+            }
+        )*
+    };
+}
+
+impl_radix_key! {
+    u8 => u8, |x| x;
+    u16 => u16, |x| x;
+    u32 => u32, |x| x;
+    u64 => u64, |x| x;
+    u128 => u128, |x| x;
+    usize => usize, |x| x;
+    i8 => u8, |x| (x as u8) ^ (1 << 7);
+    i16 => u16, |x| (x as u16) ^ (1 << 15);
+    i32 => u32, |x| (x as u32) ^ (1 << 31);
+    i64 => u64, |x| (x as u64) ^ (1 << 63);
+    i128 => u128, |x| (x as u128) ^ (1 << 127);
+    isize => usize, |x| (x as usize) ^ (1 << (usize::BITS - 1));
+    f32 => u32, |x| {
+        let bits = x.to_bits();
+        if bits >> 31 == 1 { !bits } else { bits ^ (1 << 31) }
+    };
+    f64 => u64, |x| {
+        let bits = x.to_bits();
+        if bits >> 63 == 1 { !bits } else { bits ^ (1 << 63) }
+    };
+}
+
+/// 根据输入规模自动选择排序算法。
+///
+/// 元素个数不超过 [`RADIX_THRESHOLD`] 时使用比较排序，否则使用 LSD 基数排序。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::radix_sort::radix_sort;
+///
+/// let mut ids: Vec<i64> = (0..1000).map(|i| (i * 7919) % 1000 - 500).collect();
+/// radix_sort(&mut ids);
+///
+/// assert!(ids.windows(2).all(|w| w[0] <= w[1]));
+/// ```
+pub fn radix_sort<T: RadixKey>(slice: &mut [T]) {
+    if slice.len() <= RADIX_THRESHOLD {
+        slice.sort_unstable_by(|a, b| a.radix_cmp(b));
+    } else {
+        radix_sort_lsd(slice);
+    }
+}
+
+/// LSD（低位优先）基数排序，稳定，时间复杂度 O(n * BYTES)，需要 O(n) 的额外空间。
+///
+/// 如果某一字节在所有元素上都相同，会跳过该趟分配，因此取值范围较小的数据会更快。
+pub fn radix_sort_lsd<T: RadixKey>(slice: &mut [T]) {
+    lsd_passes(slice, T::BYTES, |x, byte| x.radix_byte(byte));
+}
+
+/// 对 `slice` 做 `bytes` 趟 LSD 分配，`byte_of(x, i)` 返回元素 `x` 的第 `i` 个字节。
+fn lsd_passes<E, F>(slice: &mut [E], bytes: usize, byte_of: F)
+where
+    E: Copy,
+    F: Fn(&E, usize) -> u8,
+{
+    if slice.len() < 2 {
+        return;
+    }
+
+    let mut buffer = slice.to_vec();
+    // 数据当前所在的位置：false 表示 slice，true 表示 buffer
+    let mut in_buffer = false;
+
+    for byte in 0..bytes {
+        let (src, dst): (&[E], &mut [E]) = if in_buffer {
+            (&buffer, &mut *slice)
+        } else {
+            (&*slice, &mut buffer)
+        };
+
+        let counts = byte_counts(src, |x| byte_of(x, byte));
+        if counts.contains(&src.len()) {
+            continue;
+        }
+
+        let mut offsets = prefix_offsets(&counts);
+        for x in src {
+            let b = byte_of(x, byte) as usize;
+            dst[offsets[b]] = *x;
+            offsets[b] += 1;
+        }
+        in_buffer = !in_buffer;
+    }
+
+    if in_buffer {
+        slice.copy_from_slice(&buffer);
+    }
+}
+
+/// MSD（高位优先）基数排序，不稳定。
+///
+/// 从最高字节开始分桶，再递归处理每个桶；桶足够小时改用比较排序。
+/// 适合键很宽（如 `u128`）而元素个数相对较少的场景，可以提前结束而无需处理所有字节。
+pub fn radix_sort_msd<T: RadixKey>(slice: &mut [T]) {
+    let mut buffer = slice.to_vec();
+    msd_sort(slice, &mut buffer, T::BYTES);
+}
+
+fn msd_sort<T: RadixKey>(slice: &mut [T], buffer: &mut [T], bytes_left: usize) {
+    if slice.len() <= MSD_CUTOFF || bytes_left == 0 {
+        slice.sort_unstable_by(|a, b| a.radix_cmp(b));
+        return;
+    }
+
+    let byte = bytes_left - 1;
+    let counts = byte_counts(slice, |x| x.radix_byte(byte));
+    let starts = prefix_offsets(&counts);
+
+    let mut offsets = starts;
+    for x in slice.iter() {
+        let b = x.radix_byte(byte) as usize;
+        buffer[offsets[b]] = *x;
+        offsets[b] += 1;
+    }
+    slice.copy_from_slice(buffer);
+
+    for (b, &count) in counts.iter().enumerate() {
+        if count > 1 {
+            let range = starts[b]..starts[b] + count;
+            msd_sort(&mut slice[range.clone()], &mut buffer[range], byte);
+        }
+    }
+}
+
+/// 按键对任意类型的切片做稳定的基数排序。
+///
+/// 每个元素的键只提取一次，先对 `(键, 下标)` 做 LSD 基数排序，再原地按排列移动元素，
+/// 因此元素类型不需要实现 `Copy`。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要排序的切片。
+/// * `key` - 从元素中提取固定宽度键的函数。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::radix_sort::radix_sort_by_key;
+///
+/// let mut people = vec![("Zoe".to_string(), 25u32), ("Al".to_string(), 60), ("John".to_string(), 1)];
+/// radix_sort_by_key(&mut people, |p| p.1);
+///
+/// assert_eq!(people[0].0, "John");
+/// assert_eq!(people[2].0, "Al");
+/// ```
+pub fn radix_sort_by_key<T, K, F>(slice: &mut [T], key: F)
+where
+    K: RadixKey,
+    F: Fn(&T) -> K,
+{
+    if slice.len() < 2 {
+        return;
+    }
+
+    let mut pairs: Vec<(K, usize)> = slice.iter().map(&key).zip(0..).collect();
+    lsd_passes(&mut pairs, K::BYTES, |(k, _), byte| k.radix_byte(byte));
+
+    let mut perm: Vec<usize> = pairs.into_iter().map(|(_, i)| i).collect();
+    walk_cycles(&mut perm, |a, b| slice.swap(a, b));
+}
+
+/// 统计每个字节取值出现的次数。
+fn byte_counts<T, F>(slice: &[T], byte_of: F) -> [usize; 256]
+where
+    F: Fn(&T) -> u8,
+{
+    let mut counts = [0; 256];
+    for x in slice {
+        counts[byte_of(x) as usize] += 1;
+    }
+    counts
+}
+
+/// 由计数得到每个桶的起始位置。
+fn prefix_offsets(counts: &[usize; 256]) -> [usize; 256] {
+    let mut offsets = [0; 256];
+    let mut sum = 0;
+    for (offset, &count) in offsets.iter_mut().zip(counts) {
+        *offset = sum;
+        sum += count;
+    }
+    offsets
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{distributions::Standard, thread_rng, Rng};
+
+    fn check_all_algorithms<T>()
+    where
+        T: RadixKey + Ord + std::fmt::Debug,
+        Standard: rand::distributions::Distribution<T>,
+    {
+        let mut rng = thread_rng();
+
+        for &len in &[0, 1, 2, 63, 65, 300, 5_000] {
+            let original: Vec<T> = (&mut rng).sample_iter(Standard).take(len).collect();
+            let mut expected = original.clone();
+            expected.sort();
+
+            let mut lsd = original.clone();
+            radix_sort_lsd(&mut lsd);
+            assert_eq!(lsd, expected);
+
+            let mut msd = original.clone();
+            radix_sort_msd(&mut msd);
+            assert_eq!(msd, expected);
+
+            let mut auto = original.clone();
+            radix_sort(&mut auto);
+            assert_eq!(auto, expected);
+        }
+    }
+
+    /// 测试所有整数宽度
+    #[test]
+    fn integers_of_all_widths() {
+        check_all_algorithms::<u8>();
+        check_all_algorithms::<u16>();
+        check_all_algorithms::<u32>();
+        check_all_algorithms::<u64>();
+        check_all_algorithms::<u128>();
+        check_all_algorithms::<usize>();
+        check_all_algorithms::<i8>();
+        check_all_algorithms::<i16>();
+        check_all_algorithms::<i32>();
+        check_all_algorithms::<i64>();
+        check_all_algorithms::<i128>();
+        check_all_algorithms::<isize>();
+    }
+
+    /// 测试浮点数的排序结果与 totalOrder 一致
+    #[test]
+    fn floats_follow_total_order() {
+        let mut rng = thread_rng();
+        let mut original: Vec<f64> = (0..3_000).map(|_| rng.gen_range(-1e9..1e9)).collect();
+        original.extend([
+            f64::NAN,
+            -f64::NAN,
+            0.0,
+            -0.0,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+        ]);
+
+        let mut expected = original.clone();
+        expected.sort_by(f64::total_cmp);
+        let expected: Vec<u64> = expected.iter().map(|x| x.to_bits()).collect();
+
+        for sort in [
+            radix_sort_lsd::<f64>,
+            radix_sort_msd::<f64>,
+            radix_sort::<f64>,
+        ] {
+            let mut vec = original.clone();
+            sort(&mut vec);
+            assert_eq!(
+                vec.iter().map(|x| x.to_bits()).collect::<Vec<_>>(),
+                expected
+            );
+        }
+
+        let mut small: Vec<f32> = vec![1.5, -0.0, f32::NAN, -2.0, 0.0];
+        radix_sort(&mut small);
+        assert_eq!(&small[..2], &[-2.0, -0.0]);
+        assert!(small[2].is_sign_positive() && small[3] == 1.5 && small[4].is_nan());
+    }
+
+    /// 测试按键排序的稳定性
+    #[test]
+    fn by_key_is_stable() {
+        let mut rng = thread_rng();
+        let original: Vec<(i32, String)> = (0..2_000)
+            .map(|i| (rng.gen_range(-20..20), format!("id{i}")))
+            .collect();
+
+        let mut expected = original.clone();
+        expected.sort_by_key(|p| p.0);
+
+        let mut vec = original;
+        radix_sort_by_key(&mut vec, |p| p.0);
+        assert_eq!(vec, expected);
+    }
+}
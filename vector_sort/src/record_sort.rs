@@ -0,0 +1,795 @@
+use std::{
+    borrow::Cow,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use csv::StringRecord;
+use serde_json::{Map, Value};
+
+use crate::comparator::{Comparator, Nulls};
+use crate::float_sort::OrderedFloat;
+
+/// 结构化数据的格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// 带表头的 CSV。
+    Csv,
+    /// 每行一个 JSON 对象（JSON Lines / NDJSON）。
+    JsonLines,
+}
+
+impl Format {
+    /// 根据文件扩展名推断格式，无法识别时返回 `None`。
+    pub fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Format::Csv),
+            "jsonl" | "ndjson" | "json" => Some(Format::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "jsonl" | "ndjson" | "json" => Ok(Format::JsonLines),
+            _ => bail!("unknown format `{s}`, expected `csv` or `jsonl`"),
+        }
+    }
+}
+
+/// 排序键的取值类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyKind {
+    /// 根据列中所有非空值自动推断：全部是数字则为数字，全部是日期则为日期，否则为字符串。
+    Auto,
+    /// 数字，按数值大小比较。
+    Number,
+    /// 日期或日期时间（`YYYY-MM-DD`，可带 `THH:MM[:SS]` 及 `Z`、`±HH:MM` 时区），按时间先后比较。
+    /// 带时区的值换算为 UTC 后比较，同一列不能混用带时区和不带时区的值。
+    Date,
+    /// 字符串，按字节序比较。
+    Text,
+}
+
+/// 一个排序键：列名、方向和取值类型。
+///
+/// 可以从 `列名[:asc|desc][:auto|num|date|str]` 形式的字符串解析，例如 `age:desc:num`。
+/// 修饰符的顺序不限，列名本身可以包含 `:`。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SortKey {
+    pub column: String,
+    pub descending: bool,
+    pub kind: KeyKind,
+}
+
+impl FromStr for SortKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts: Vec<&str> = s.split(':').collect();
+        let mut descending = None;
+        let mut kind = None;
+
+        // 从右往左识别修饰符，剩下的部分就是列名
+        while parts.len() > 1 {
+            let last = parts[parts.len() - 1];
+            match last.to_ascii_lowercase().as_str() {
+                "asc" if descending.is_none() => descending = Some(false),
+                "desc" if descending.is_none() => descending = Some(true),
+                "auto" if kind.is_none() => kind = Some(KeyKind::Auto),
+                "num" | "number" if kind.is_none() => kind = Some(KeyKind::Number),
+                "date" if kind.is_none() => kind = Some(KeyKind::Date),
+                "str" | "string" if kind.is_none() => kind = Some(KeyKind::Text),
+                _ => break,
+            }
+            parts.pop();
+        }
+
+        let column = parts.join(":");
+        if column.is_empty() {
+            bail!("sort key `{s}` has an empty column name");
+        }
+
+        Ok(SortKey {
+            column,
+            descending: descending.unwrap_or(false),
+            kind: kind.unwrap_or(KeyKind::Auto),
+        })
+    }
+}
+
+/// 一条记录，保留原始内容以便原样输出。
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    Csv(StringRecord),
+    Json(Map<String, Value>),
+}
+
+/// 读入内存的一组记录。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Records {
+    format: Format,
+    /// CSV 的表头；JSON Lines 没有表头
+    headers: Option<StringRecord>,
+    rows: Vec<Row>,
+}
+
+impl Records {
+    /// 从 `reader` 中读取指定格式的全部记录。
+    pub fn read<R: Read>(reader: R, format: Format) -> Result<Records> {
+        match format {
+            Format::Csv => {
+                let mut reader = csv::Reader::from_reader(reader);
+                let headers = reader.headers()?.clone();
+                let rows = reader
+                    .records()
+                    .map(|r| r.map(Row::Csv))
+                    .collect::<csv::Result<Vec<Row>>>()?;
+
+                Ok(Records {
+                    format,
+                    headers: Some(headers),
+                    rows,
+                })
+            }
+            Format::JsonLines => {
+                let mut rows = Vec::new();
+                for (i, line) in BufReader::new(reader).lines().enumerate() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str(&line)
+                        .with_context(|| format!("invalid JSON on line {}", i + 1))?
+                    {
+                        Value::Object(map) => rows.push(Row::Json(map)),
+                        _ => bail!("line {} is not a JSON object", i + 1),
+                    }
+                }
+
+                Ok(Records {
+                    format,
+                    headers: None,
+                    rows,
+                })
+            }
+        }
+    }
+
+    /// 追加另一组同格式的记录，CSV 要求两者表头一致。
+    pub fn append(&mut self, other: Records) -> Result<()> {
+        if self.format != other.format {
+            bail!(
+                "cannot merge {:?} input with {:?} input",
+                self.format,
+                other.format
+            );
+        }
+        if self.headers != other.headers {
+            bail!("CSV inputs have different headers");
+        }
+        self.rows.extend(other.rows);
+        Ok(())
+    }
+
+    /// 记录条数。
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// 是否没有任何记录。
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// 输入的格式。
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// 按 `keys` 对记录排序，返回每个键最终使用的类型（自动推断的结果）。
+    ///
+    /// 空值（空字符串、JSON 的 `null` 或缺失字段）无论升序降序都排在最后。
+    /// `stable` 为 `true` 时使用稳定排序，键相同的记录保持输入中的顺序。
+    /// 日期键中同时出现带时区和不带时区的值时返回错误。
+    pub fn sort(&mut self, keys: &[SortKey], stable: bool) -> Result<Vec<KeyKind>> {
+        let columns = keys
+            .iter()
+            .map(|k| self.resolve_column(&k.column))
+            .collect::<Result<Vec<Column>>>()?;
+
+        let kinds = keys
+            .iter()
+            .zip(&columns)
+            .map(|(key, column)| match key.kind {
+                KeyKind::Auto => self.infer_kind(column),
+                kind => kind,
+            })
+            .collect::<Vec<KeyKind>>();
+
+        // 每条记录的键只解析一次；先解析全部键再取出记录，解析失败时记录保持不变
+        let values = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                columns
+                    .iter()
+                    .zip(&kinds)
+                    .zip(keys)
+                    .map(|((column, &kind), key)| {
+                        row.cell(column)
+                            .map(|cell| KeyValue::parse(&cell, kind))
+                            .transpose()
+                            .with_context(|| {
+                                format!("record {}: bad value for key `{}`", i + 1, key.column)
+                            })
+                    })
+                    .collect::<Result<Vec<Option<KeyValue>>>>()
+            })
+            .collect::<Result<Vec<Vec<Option<KeyValue>>>>>()?;
+        for (i, key) in keys.iter().enumerate() {
+            let mut zones = values.iter().filter_map(|v| match &v[i] {
+                Some(KeyValue::Date(d)) => Some(d.zoned),
+                _ => None,
+            });
+            if let Some(first) = zones.next() {
+                if zones.any(|zoned| zoned != first) {
+                    bail!(
+                        "key `{}` mixes dates with and without a time zone",
+                        key.column
+                    );
+                }
+            }
+        }
+        let mut keyed: Vec<(Vec<Option<KeyValue>>, Row)> = values
+            .into_iter()
+            .zip(std::mem::take(&mut self.rows))
+            .collect();
+
+        let mut cmp = Comparator::new();
+        for (i, key) in keys.iter().enumerate() {
+            let descending = key.descending;
+            cmp = cmp.then_with(move |a: &(Vec<Option<KeyValue>>, Row), b| {
+                Nulls::Last.compare(a.0[i].as_ref(), b.0[i].as_ref(), |x, y| {
+                    if descending {
+                        y.cmp(x)
+                    } else {
+                        x.cmp(y)
+                    }
+                })
+            });
+        }
+
+        if stable {
+            cmp.sort(&mut keyed);
+        } else {
+            cmp.sort_unstable(&mut keyed);
+        }
+
+        self.rows = keyed.into_iter().map(|(_, row)| row).collect();
+        Ok(kinds)
+    }
+
+    /// 以 `format` 格式写出全部记录，必要时在 CSV 与 JSON Lines 之间转换。
+    pub fn write<W: Write>(&self, writer: W, format: Format) -> Result<()> {
+        match format {
+            Format::Csv => self.write_csv(writer),
+            Format::JsonLines => self.write_json_lines(writer),
+        }
+    }
+
+    fn write_csv<W: Write>(&self, writer: W) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        let headers = match &self.headers {
+            Some(headers) => headers.clone(),
+            None => self.json_columns(),
+        };
+        writer.write_record(&headers)?;
+
+        for row in &self.rows {
+            match row {
+                Row::Csv(record) => writer.write_record(record)?,
+                Row::Json(map) => writer.write_record(headers.iter().map(|h| {
+                    map.get(h)
+                        .map(json_to_text)
+                        .unwrap_or_default()
+                        .into_owned()
+                }))?,
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_json_lines<W: Write>(&self, mut writer: W) -> Result<()> {
+        for row in &self.rows {
+            match row {
+                Row::Json(map) => serde_json::to_writer(&mut writer, map)?,
+                Row::Csv(record) => {
+                    let headers = self.headers.as_ref().expect("CSV records have headers");
+                    let map: Map<String, Value> = headers
+                        .iter()
+                        .zip(record)
+                        .map(|(h, v)| (h.to_string(), Value::String(v.to_string())))
+                        .collect();
+                    serde_json::to_writer(&mut writer, &map)?;
+                }
+            }
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// JSON 记录中出现过的所有字段，按首次出现的顺序排列。
+    fn json_columns(&self) -> StringRecord {
+        let mut columns: Vec<&str> = Vec::new();
+        for row in &self.rows {
+            if let Row::Json(map) = row {
+                for key in map.keys() {
+                    if !columns.contains(&key.as_str()) {
+                        columns.push(key);
+                    }
+                }
+            }
+        }
+        StringRecord::from(columns)
+    }
+
+    fn resolve_column(&self, name: &str) -> Result<Column> {
+        match &self.headers {
+            Some(headers) => headers
+                .iter()
+                .position(|h| h == name)
+                .map(Column::Index)
+                .ok_or_else(|| anyhow!("no column named `{name}`")),
+            // JSON Lines 没有表头：只要有一条记录含有该字段即可，没有记录时无从检查
+            None => {
+                let present = self.rows.is_empty()
+                    || self
+                        .rows
+                        .iter()
+                        .any(|row| matches!(row, Row::Json(map) if map.contains_key(name)));
+                if present {
+                    Ok(Column::Name(name.to_string()))
+                } else {
+                    Err(anyhow!("no record has a field named `{name}`"))
+                }
+            }
+        }
+    }
+
+    /// 根据列中所有非空值推断类型。
+    fn infer_kind(&self, column: &Column) -> KeyKind {
+        let mut cells = self
+            .rows
+            .iter()
+            .filter_map(|row| row.cell(column))
+            .peekable();
+        if cells.peek().is_none() {
+            return KeyKind::Text;
+        }
+
+        let (mut all_numbers, mut all_dates) = (true, true);
+        for cell in cells {
+            all_numbers = all_numbers && parse_number(&cell).is_some();
+            all_dates = all_dates && DateTime::parse(&cell).is_some();
+            if !all_numbers && !all_dates {
+                return KeyKind::Text;
+            }
+        }
+
+        if all_numbers {
+            KeyKind::Number
+        } else {
+            KeyKind::Date
+        }
+    }
+}
+
+/// 已解析的列引用：CSV 按下标访问，JSON 按字段名访问。
+enum Column {
+    Index(usize),
+    Name(String),
+}
+
+impl Row {
+    /// 取出某一列的文本，空字符串、`null` 和缺失字段都视为空值。
+    fn cell(&self, column: &Column) -> Option<Cow<'_, str>> {
+        let text = match (self, column) {
+            (Row::Csv(record), Column::Index(i)) => Cow::Borrowed(record.get(*i)?),
+            (Row::Json(map), Column::Name(name)) => match map.get(name)? {
+                Value::Null => return None,
+                value => json_to_text(value),
+            },
+            _ => return None,
+        };
+
+        if text.trim().is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+}
+
+fn json_to_text(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::String(s) => Cow::Borrowed(s),
+        Value::Null => Cow::Borrowed(""),
+        other => Cow::Owned(other.to_string()),
+    }
+}
+
+/// 解析后的键值，同一列的值类型总是相同的。
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum KeyValue {
+    Number(OrderedFloat<f64>),
+    Date(DateTime),
+    Text(String),
+}
+
+impl KeyValue {
+    fn parse(cell: &str, kind: KeyKind) -> Result<KeyValue> {
+        match kind {
+            KeyKind::Number => parse_number(cell)
+                .map(|n| KeyValue::Number(OrderedFloat(n)))
+                .ok_or_else(|| anyhow!("`{cell}` is not a number")),
+            KeyKind::Date => DateTime::parse(cell)
+                .map(KeyValue::Date)
+                .ok_or_else(|| anyhow!("`{cell}` is not a date")),
+            KeyKind::Text | KeyKind::Auto => Ok(KeyValue::Text(cell.to_string())),
+        }
+    }
+}
+
+/// 解析有限的数字，`NaN`、`inf` 等不视为数字。
+fn parse_number(cell: &str) -> Option<f64> {
+    let n: f64 = cell.trim().parse().ok()?;
+    // 统一 -0.0 与 +0.0，使它们在排序时相等
+    n.is_finite().then_some(n + 0.0)
+}
+
+/// 简化的 ISO 8601 日期时间，字段顺序即比较顺序。
+///
+/// 带时区（`Z` 或 `±HH:MM`）的值统一换算为 UTC；不带时区的值按本地时间原样保存，
+/// 两者无法比较，同一列中混用时排序会报错。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct DateTime {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanos: u32,
+    zoned: bool,
+}
+
+impl DateTime {
+    /// 解析 `YYYY-MM-DD`（或 `YYYY/MM/DD`），可选地跟随 `T` 或空格以及 `HH:MM[:SS[.fff]]`，
+    /// 时间后可以带 `Z` 或 `±HH:MM`、`±HHMM`、`±HH` 形式的时区偏移。
+    fn parse(s: &str) -> Option<DateTime> {
+        let s = s.trim();
+        let (date, time) = match s.find(['T', ' ']) {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let mut parts = date.split(['-', '/']);
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some()
+            || !(1..=12).contains(&month)
+            || !(1..=days_in_month(year, month)).contains(&day)
+        {
+            return None;
+        }
+
+        let mut dt = DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanos: 0,
+            zoned: false,
+        };
+
+        if let Some(time) = time {
+            let (time, offset) = split_offset(time)?;
+            let mut parts = time.split(':');
+            dt.hour = parts.next()?.parse().ok()?;
+            dt.minute = parts.next()?.parse().ok()?;
+            if let Some(sec) = parts.next() {
+                let (sec, frac) = sec.split_once('.').unwrap_or((sec, ""));
+                dt.second = sec.parse().ok()?;
+                if !frac.is_empty() {
+                    if frac.len() > 9 || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                        return None;
+                    }
+                    dt.nanos = format!("{frac:0<9}").parse().ok()?;
+                }
+            }
+            if parts.next().is_some() || dt.hour > 23 || dt.minute > 59 || dt.second > 60 {
+                return None;
+            }
+            if let Some(offset) = offset {
+                dt.shift_to_utc(offset);
+            }
+        }
+
+        Some(dt)
+    }
+
+    /// 减去 `offset` 分钟的时区偏移，换算为 UTC，必要时跨越日期。
+    fn shift_to_utc(&mut self, offset: i32) {
+        let minutes = i32::from(self.hour) * 60 + i32::from(self.minute) - offset;
+        let minutes_per_day = 24 * 60;
+        self.hour = (minutes.rem_euclid(minutes_per_day) / 60) as u8;
+        self.minute = (minutes.rem_euclid(60)) as u8;
+        self.zoned = true;
+
+        match minutes.div_euclid(minutes_per_day) {
+            -1 if self.day > 1 => self.day -= 1,
+            -1 => {
+                (self.year, self.month) = match self.month {
+                    1 => (self.year - 1, 12),
+                    m => (self.year, m - 1),
+                };
+                self.day = days_in_month(self.year, self.month);
+            }
+            1 if self.day < days_in_month(self.year, self.month) => self.day += 1,
+            1 => {
+                (self.year, self.month) = match self.month {
+                    12 => (self.year + 1, 1),
+                    m => (self.year, m + 1),
+                };
+                self.day = 1;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 从时间部分末尾拆出时区偏移（以分钟计），`Z` 为 0，没有时区时为 `None`。
+fn split_offset(time: &str) -> Option<(&str, Option<i32>)> {
+    let time = time.trim();
+    if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        return Some((time.trim_end(), Some(0)));
+    }
+    let Some(i) = time.rfind(['+', '-']) else {
+        return Some((time, None));
+    };
+
+    let (sign, offset) = (&time[i..i + 1], &time[i + 1..]);
+    let (hours, minutes) = match offset.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "00"),
+    };
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    let offset = hours * 60 + minutes;
+    Some((
+        time[..i].trim_end(),
+        Some(if sign == "-" { -offset } else { offset }),
+    ))
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_csv(input: &str, keys: &[&str], stable: bool) -> String {
+        let mut records = Records::read(input.as_bytes(), Format::Csv).unwrap();
+        let keys: Vec<SortKey> = keys.iter().map(|k| k.parse().unwrap()).collect();
+        records.sort(&keys, stable).unwrap();
+
+        let mut out = Vec::new();
+        records.write(&mut out, Format::Csv).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// 测试排序键的解析
+    #[test]
+    fn parse_sort_key() {
+        let key: SortKey = "age:desc:num".parse().unwrap();
+        assert_eq!(
+            key,
+            SortKey {
+                column: "age".to_string(),
+                descending: true,
+                kind: KeyKind::Number,
+            }
+        );
+
+        let key: SortKey = "a:b:date".parse().unwrap();
+        assert_eq!(key.column, "a:b");
+        assert_eq!(key.kind, KeyKind::Date);
+        assert!(!key.descending);
+
+        assert!("".parse::<SortKey>().is_err());
+        assert!("desc".parse::<SortKey>().is_ok());
+    }
+
+    /// 测试类型推断：数字列按数值排序，日期列按时间排序
+    #[test]
+    fn infers_numeric_and_date_columns() {
+        let input = "name,age,joined\n\
+                     Zoe,25,2021-03-01\n\
+                     Al,100,2020-12-31T23:59:59\n\
+                     John,9,2021-01-15\n";
+
+        assert_eq!(
+            sorted_csv(input, &["age"], true),
+            "name,age,joined\nJohn,9,2021-01-15\nZoe,25,2021-03-01\nAl,100,2020-12-31T23:59:59\n"
+        );
+        assert_eq!(
+            sorted_csv(input, &["joined:desc"], true),
+            "name,age,joined\nZoe,25,2021-03-01\nJohn,9,2021-01-15\nAl,100,2020-12-31T23:59:59\n"
+        );
+        // 强制按字符串比较时 "100" < "25" < "9"
+        assert_eq!(
+            sorted_csv(input, &["age:str"], true),
+            "name,age,joined\nAl,100,2020-12-31T23:59:59\nZoe,25,2021-03-01\nJohn,9,2021-01-15\n"
+        );
+    }
+
+    /// 测试多键、稳定性以及空值排在最后
+    #[test]
+    fn multiple_keys_stability_and_nulls() {
+        let input = "team,score,id\na,1,1\nb,,2\na,3,3\nb,2,4\na,1,5\n";
+
+        assert_eq!(
+            sorted_csv(input, &["team", "score:desc"], true),
+            "team,score,id\na,3,3\na,1,1\na,1,5\nb,2,4\nb,,2\n"
+        );
+        assert_eq!(
+            sorted_csv(input, &["score"], true),
+            "team,score,id\na,1,1\na,1,5\nb,2,4\na,3,3\nb,,2\n"
+        );
+    }
+
+    /// 测试 JSON Lines 的读写与格式转换
+    #[test]
+    fn json_lines_round_trip() {
+        let input = r#"{"name":"Zoe","age":25}
+{"name":"Al","age":60,"city":"Paris"}
+
+{"name":"John","age":null}
+"#;
+        let mut records = Records::read(input.as_bytes(), Format::JsonLines).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let kinds = records.sort(&["age:desc".parse().unwrap()], true).unwrap();
+        assert_eq!(kinds, [KeyKind::Number]);
+
+        let mut out = Vec::new();
+        records.write(&mut out, Format::JsonLines).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"name\":\"Al\",\"age\":60,\"city\":\"Paris\"}\n\
+             {\"name\":\"Zoe\",\"age\":25}\n\
+             {\"name\":\"John\",\"age\":null}\n"
+        );
+
+        let mut out = Vec::new();
+        records.write(&mut out, Format::Csv).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "name,age,city\nAl,60,Paris\nZoe,25,\nJohn,,\n"
+        );
+    }
+
+    /// 测试错误情况
+    #[test]
+    fn reports_errors() {
+        let mut records = Records::read("a,b\n1,x\n".as_bytes(), Format::Csv).unwrap();
+        assert!(records.sort(&["missing".parse().unwrap()], true).is_err());
+        assert!(records.sort(&["b:num".parse().unwrap()], true).is_err());
+        // 排序失败时记录保持不变
+        assert_eq!(records.len(), 1);
+
+        // JSON Lines 中没有任何记录含有的字段同样是错误，字段为 null 则不是
+        let mut records =
+            Records::read("{\"a\":1}\n{\"b\":null}\n".as_bytes(), Format::JsonLines).unwrap();
+        let err = records.sort(&["c".parse().unwrap()], true).unwrap_err();
+        assert_eq!(err.to_string(), "no record has a field named `c`");
+        assert!(records.sort(&["b".parse().unwrap()], true).is_ok());
+        let mut empty = Records::read("".as_bytes(), Format::JsonLines).unwrap();
+        assert!(empty.sort(&["c".parse().unwrap()], true).is_ok());
+
+        assert!(Records::read("[1, 2]\n".as_bytes(), Format::JsonLines).is_err());
+        assert!(Records::read("{oops\n".as_bytes(), Format::JsonLines).is_err());
+    }
+
+    /// 测试日期解析
+    #[test]
+    fn parse_dates() {
+        assert!(DateTime::parse("2024-02-29").is_some());
+        assert!(DateTime::parse("2024/02/29 08:30").is_some());
+        assert!(DateTime::parse("2024-02-29T08:30:15.25Z").is_some());
+        assert!(DateTime::parse("2024-13-01").is_none());
+        assert!(DateTime::parse("2024-01-01T25:00").is_none());
+        assert!(DateTime::parse("hello").is_none());
+
+        assert!(DateTime::parse("2024-01-01T00:00:00.5") > DateTime::parse("2024-01-01"));
+        assert!(DateTime::parse("2023-02-29").is_none());
+        assert!(DateTime::parse("2024-01-01T10:00+2:00").is_none());
+        assert!(DateTime::parse("2024-01-01T10:00+24:00").is_none());
+    }
+
+    /// 测试时区偏移换算为 UTC
+    #[test]
+    fn parse_time_zones() {
+        let utc = DateTime::parse("2024-01-01T08:00:00Z");
+        assert!(utc.unwrap().zoned);
+        assert!(!DateTime::parse("2024-01-01T08:00:00").unwrap().zoned);
+        assert_eq!(DateTime::parse("2024-01-01T10:00:00+02:00"), utc);
+        assert_eq!(DateTime::parse("2024-01-01 10:00+0200"), utc);
+        assert_eq!(DateTime::parse("2024-01-01T03:30-04:30"), utc);
+        assert_eq!(DateTime::parse("2024-01-01T08:00:00 z"), utc);
+
+        // 换算时跨越日期、月份和年份
+        assert_eq!(
+            DateTime::parse("2024-03-01T01:00+02"),
+            DateTime::parse("2024-02-29T23:00Z")
+        );
+        assert_eq!(
+            DateTime::parse("2023-12-31T22:00-03:00"),
+            DateTime::parse("2024-01-01T01:00Z")
+        );
+    }
+
+    /// 测试混用 `Z`、偏移和不带时区的日期
+    #[test]
+    fn mixed_time_zones() {
+        let input = "id,at\n\
+                     a,2024-01-01T10:00:00+02:00\n\
+                     b,2024-01-01T09:00:00Z\n\
+                     c,2024-01-01T07:30:00-00:30\n";
+        let mut records = Records::read(input.as_bytes(), Format::Csv).unwrap();
+        let kinds = records.sort(&["at".parse().unwrap()], true).unwrap();
+        assert_eq!(kinds, [KeyKind::Date]);
+        assert_eq!(
+            sorted_csv(input, &["at"], true),
+            "id,at\na,2024-01-01T10:00:00+02:00\nc,2024-01-01T07:30:00-00:30\nb,2024-01-01T09:00:00Z\n"
+        );
+
+        let mixed = "id,at\na,2024-01-01T10:00:00+02:00\nb,2024-01-01T09:00:00\n";
+        for key in ["at", "at:date"] {
+            let mut records = Records::read(mixed.as_bytes(), Format::Csv).unwrap();
+            let err = records.sort(&[key.parse().unwrap()], true).unwrap_err();
+            assert!(err.to_string().contains("time zone"), "{err}");
+            assert_eq!(records.len(), 2);
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use std::cmp::Ordering;
+
+use crate::comparator::Compare;
+use crate::heap;
+
+/// 重排切片，使下标 `n` 处恰好是完整排序后应在该位置的元素，并返回它的可变引用。
+///
+/// 排在 `n` 之前的元素都不大于它，之后的元素都不小于它，但两侧内部的顺序不确定。
+/// 平均时间复杂度 O(n)。
+///
+/// # 参数
+///
+/// * `slice` - 一个可变引用，指向要处理的切片。
+/// * `n` - 目标下标，必须小于切片长度，否则会 panic。
+/// * `cmp` - 比较器，可以是 `sort_by` 形式的闭包或 [`Comparator`](crate::comparator::Comparator)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::select::select_nth;
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// let mut ages = vec![25, 60, 1, 42, 18];
+/// let median = *select_nth(&mut ages, 2, DefaultOrder);
+///
+/// assert_eq!(median, 25);
+/// ```
+pub fn select_nth<T, C: Compare<T>>(slice: &mut [T], n: usize, cmp: C) -> &mut T {
+    slice.select_nth_unstable_by(n, |a, b| cmp.compare(a, b)).1
+}
+
+/// 部分排序：把最小的 `k` 个元素按顺序放到切片开头，其余元素的顺序不确定。
+///
+/// 先用快速选择把前 `k` 个元素分离出来，再只对它们排序，
+/// 平均时间复杂度 O(n + k log k)，不分配额外内存。结果不保证稳定。
+/// `k` 大于切片长度时等同于完整排序。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::select::partial_sort;
+///
+/// let mut vec = vec![9, 3, 7, 1, 8, 2];
+/// partial_sort(&mut vec, 3, |a: &i32, b: &i32| a.cmp(b));
+///
+/// assert_eq!(&vec[..3], &[1, 2, 3]);
+/// ```
+pub fn partial_sort<T, C: Compare<T>>(slice: &mut [T], k: usize, cmp: C) {
+    let k = k.min(slice.len());
+    if k == 0 {
+        return;
+    }
+
+    if k < slice.len() {
+        slice.select_nth_unstable_by(k - 1, |a, b| cmp.compare(a, b));
+    }
+    slice[..k].sort_unstable_by(|a, b| cmp.compare(a, b));
+}
+
+/// 返回切片中按比较器排序后的前 `k` 个元素的引用，不修改也不克隆原数据。
+///
+/// 结果与稳定排序后取前 `k` 个元素完全一致（相等的元素保持原有顺序）。
+/// 时间复杂度 O(n log k)，额外空间 O(k)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::select::top_k;
+/// use vector_sort::comparator::by_desc;
+///
+/// let people = vec![("Zoe", 25), ("Al", 60), ("John", 1), ("Ann", 60)];
+/// // 年龄最大的两个人
+/// let oldest = top_k(&people, 2, by_desc(|p: &(&str, u32)| p.1));
+///
+/// assert_eq!(oldest, [&("Al", 60), &("Ann", 60)]);
+/// ```
+pub fn top_k<T, C: Compare<T>>(slice: &[T], k: usize, cmp: C) -> Vec<&T> {
+    top_k_iter(slice, k, |a: &&T, b: &&T| cmp.compare(a, b))
+}
+
+/// 从任意迭代器中流式地取出排序后的前 `k` 个元素，只保留 O(k) 个元素在内存中。
+///
+/// 参见 [`TopK`]。
+pub fn top_k_iter<T, I, C>(iter: I, k: usize, cmp: C) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    C: Compare<T>,
+{
+    let mut top = TopK::new(k, cmp);
+    top.extend(iter);
+    top.into_sorted_vec()
+}
+
+/// 流式 top-k 收集器。
+///
+/// 内部维护一个大小不超过 `k` 的大顶堆，堆顶是当前保留的元素中最“差”的一个；
+/// 新元素严格优于堆顶时才替换堆顶，因此相等的元素先到先得，
+/// 最终结果与对全部输入做稳定排序后取前 `k` 个一致。
+///
+/// 每个元素的处理时间为 O(log k)。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::select::TopK;
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// let mut top = TopK::new(3, DefaultOrder);
+/// for x in [5, 1, 9, 3, 7] {
+///     top.push(x);
+/// }
+///
+/// assert_eq!(top.into_sorted_vec(), [1, 3, 5]);
+/// ```
+pub struct TopK<T, C> {
+    k: usize,
+    /// 元素及其到达的序号，序号用于在相等时保持先后顺序
+    heap: Vec<(T, usize)>,
+    seen: usize,
+    cmp: C,
+}
+
+impl<T, C: Compare<T>> TopK<T, C> {
+    /// 创建一个最多保留 `k` 个元素的收集器。
+    pub fn new(k: usize, cmp: C) -> Self {
+        TopK {
+            k,
+            // k 可能远大于实际输入，预分配的空间设置上限
+            heap: Vec::with_capacity(k.min(1024)),
+            seen: 0,
+            cmp,
+        }
+    }
+
+    /// 加入一个元素。
+    pub fn push(&mut self, item: T) {
+        let seq = self.seen;
+        self.seen += 1;
+
+        if self.k == 0 {
+            return;
+        }
+
+        let cmp = &self.cmp;
+        let worse = |a: &(T, usize), b: &(T, usize)| ordered(cmp, a, b).is_gt();
+
+        if self.heap.len() < self.k {
+            self.heap.push((item, seq));
+            let last = self.heap.len() - 1;
+            heap::sift_up(&mut self.heap, last, &worse);
+        } else if cmp.compare(&item, &self.heap[0].0).is_lt() {
+            self.heap[0] = (item, seq);
+            heap::sift_down(&mut self.heap, 0, &worse);
+        }
+    }
+
+    /// 当前保留的元素个数。
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// 是否还没有保留任何元素。
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// 按比较器顺序返回保留的元素。
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let cmp = &self.cmp;
+        self.heap.sort_unstable_by(|a, b| ordered(cmp, a, b));
+        self.heap.into_iter().map(|(item, _)| item).collect()
+    }
+}
+
+impl<T, C: Compare<T>> Extend<T> for TopK<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+/// 先按比较器、再按到达顺序比较。
+fn ordered<T, C: Compare<T>>(cmp: &C, (x, i): &(T, usize), (y, j): &(T, usize)) -> Ordering {
+    cmp.compare(x, y).then(i.cmp(j))
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::{by, DefaultOrder};
+    use rand::{thread_rng, Rng};
+
+    fn random_vec(len: usize, max: u32) -> Vec<u32> {
+        let mut rng = thread_rng();
+        (0..len).map(|_| rng.gen_range(0..max)).collect()
+    }
+
+    /// 测试 select_nth 与完整排序后的结果一致
+    #[test]
+    fn select_nth_matches_sorted() {
+        for len in 1..100 {
+            let original = random_vec(len, 20);
+            let mut sorted = original.clone();
+            sorted.sort();
+
+            for n in 0..len {
+                let mut vec = original.clone();
+                let nth = *select_nth(&mut vec, n, DefaultOrder);
+                assert_eq!(nth, sorted[n]);
+                assert!(vec[..n].iter().all(|&x| x <= nth));
+                assert!(vec[n + 1..].iter().all(|&x| x >= nth));
+            }
+        }
+    }
+
+    /// 测试部分排序
+    #[test]
+    fn partial_sort_prefix() {
+        for len in 0..100 {
+            let original = random_vec(len, 1000);
+            let mut sorted = original.clone();
+            sorted.sort();
+
+            for k in [0, 1, len / 2, len, len + 5] {
+                let mut vec = original.clone();
+                partial_sort(&mut vec, k, DefaultOrder);
+
+                let k = k.min(len);
+                assert_eq!(&vec[..k], &sorted[..k]);
+
+                vec.sort();
+                assert_eq!(vec, sorted);
+            }
+        }
+    }
+
+    /// 测试 top_k 与稳定排序后取前 k 个完全一致，包括相等元素的顺序
+    #[test]
+    fn top_k_is_stable_prefix() {
+        for len in 0..200 {
+            let original: Vec<(u32, usize)> = random_vec(len, 10)
+                .into_iter()
+                .enumerate()
+                .map(|(i, x)| (x, i))
+                .collect();
+            let mut sorted = original.clone();
+            sorted.sort_by_key(|&(x, _)| std::cmp::Reverse(x));
+
+            for k in [0, 1, 7, len, len + 1] {
+                let top = top_k(&original, k, |a: &(u32, usize), b: &(u32, usize)| {
+                    b.0.cmp(&a.0)
+                });
+                let expected: Vec<&(u32, usize)> = sorted.iter().take(k).collect();
+                assert_eq!(top, expected);
+
+                let streamed = top_k_iter(
+                    original.iter().copied(),
+                    k,
+                    by(|&(x, _): &(u32, usize)| std::cmp::Reverse(x)),
+                );
+                assert_eq!(streamed, &sorted[..k.min(len)]);
+            }
+        }
+    }
+
+    /// 测试流式收集器的计数
+    #[test]
+    fn top_k_collector() {
+        let mut top = TopK::new(2, DefaultOrder);
+        assert!(top.is_empty());
+
+        top.extend([3, 1, 2]);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top.into_sorted_vec(), [1, 2]);
+    }
+}
@@ -0,0 +1,416 @@
+use std::{cmp::Ordering, ops::Range};
+
+use crate::comparator::Compare;
+use crate::heap;
+
+/// 去重时保留哪一个元素。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    /// 保留每组相等元素中的第一个。
+    First,
+    /// 保留每组相等元素中的最后一个。
+    Last,
+}
+
+/// 检查切片是否已按比较器排好序（允许相等的相邻元素）。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::is_sorted_by;
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// assert!(is_sorted_by(&[1, 2, 2, 5], DefaultOrder));
+/// assert!(!is_sorted_by(&[1, 3, 2], DefaultOrder));
+/// ```
+pub fn is_sorted_by<T, C: Compare<T>>(slice: &[T], cmp: C) -> bool {
+    first_unsorted(slice, cmp).is_none()
+}
+
+/// 返回第一个比前一个元素小的元素下标，切片有序时返回 `None`，便于定位问题数据。
+pub fn first_unsorted<T, C: Compare<T>>(slice: &[T], cmp: C) -> Option<usize> {
+    slice
+        .windows(2)
+        .position(|w| cmp.compare(&w[0], &w[1]).is_gt())
+        .map(|i| i + 1)
+}
+
+/// 在有序切片中查找第一个不小于 `probe` 的位置。
+pub fn lower_bound<T, C: Compare<T>>(slice: &[T], probe: &T, cmp: C) -> usize {
+    slice.partition_point(|x| cmp.compare(x, probe).is_lt())
+}
+
+/// 在有序切片中查找第一个大于 `probe` 的位置。
+pub fn upper_bound<T, C: Compare<T>>(slice: &[T], probe: &T, cmp: C) -> usize {
+    slice.partition_point(|x| cmp.compare(x, probe).is_le())
+}
+
+/// 返回有序切片中所有与 `probe` 相等的元素的下标范围，不存在时返回空范围（即插入位置）。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::equal_range;
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// let vec = vec![1, 2, 2, 2, 5];
+///
+/// assert_eq!(equal_range(&vec, &2, DefaultOrder), 1..4);
+/// assert_eq!(equal_range(&vec, &3, DefaultOrder), 4..4);
+/// ```
+pub fn equal_range<T, C: Compare<T>>(slice: &[T], probe: &T, cmp: C) -> Range<usize> {
+    let cmp = |a: &T, b: &T| cmp.compare(a, b);
+    let start = lower_bound(slice, probe, cmp);
+    let end = start + upper_bound(&slice[start..], probe, cmp);
+    start..end
+}
+
+/// 按键在有序切片中查找第一个键不小于 `key` 的位置，切片须按同一个键排序。
+pub fn lower_bound_by_key<T, K, F>(slice: &[T], key: &K, f: F) -> usize
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    slice.partition_point(|x| f(x) < *key)
+}
+
+/// 按键在有序切片中查找第一个键大于 `key` 的位置，切片须按同一个键排序。
+pub fn upper_bound_by_key<T, K, F>(slice: &[T], key: &K, f: F) -> usize
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    slice.partition_point(|x| f(x) <= *key)
+}
+
+/// 按键返回有序切片中键等于 `key` 的元素下标范围。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::equal_range_by_key;
+///
+/// let people = vec![("Al", 20), ("Zoe", 25), ("John", 25), ("Ann", 60)];
+/// let range = equal_range_by_key(&people, &25, |p| p.1);
+///
+/// assert_eq!(&people[range], &[("Zoe", 25), ("John", 25)]);
+/// ```
+pub fn equal_range_by_key<T, K, F>(slice: &[T], key: &K, f: F) -> Range<usize>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let start = lower_bound_by_key(slice, key, &f);
+    let end = start + upper_bound_by_key(&slice[start..], key, &f);
+    start..end
+}
+
+/// 对有序向量去重：比较器认为相等的相邻元素只保留一个。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::{dedup_by, Keep};
+///
+/// let mut vec = vec![(1, 'a'), (1, 'b'), (2, 'c'), (2, 'd')];
+/// dedup_by(&mut vec, |a: &(i32, char), b: &(i32, char)| a.0.cmp(&b.0), Keep::Last);
+///
+/// assert_eq!(vec, [(1, 'b'), (2, 'd')]);
+/// ```
+pub fn dedup_by<T, C: Compare<T>>(vec: &mut Vec<T>, cmp: C, keep: Keep) {
+    match keep {
+        // `dedup_by` 的闭包参数顺序为 (后一个, 已保留的前一个)，返回 true 时移除后一个
+        Keep::First => vec.dedup_by(|next, kept| cmp.compare(kept, next).is_eq()),
+        Keep::Last => vec.dedup_by(|next, kept| {
+            let equal = cmp.compare(kept, next).is_eq();
+            if equal {
+                // 用后一个元素覆盖已保留的元素，再移除后一个
+                std::mem::swap(kept, next);
+            }
+            equal
+        }),
+    }
+}
+
+/// 对按键排序的向量去重：键相同的相邻元素只保留一个。
+pub fn dedup_by_key<T, K, F>(vec: &mut Vec<T>, f: F, keep: Keep)
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    dedup_by(vec, |a: &T, b: &T| f(a).cmp(&f(b)), keep);
+}
+
+/// 有序多重集合的并集：每个元素出现的次数为两边次数的较大值。
+///
+/// 相等的元素优先取自 `a`。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::{difference, intersection, union};
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// let a = [1, 2, 2, 4];
+/// let b = [2, 3, 4, 4];
+///
+/// assert_eq!(union(&a, &b, DefaultOrder), [1, 2, 2, 3, 4, 4]);
+/// assert_eq!(intersection(&a, &b, DefaultOrder), [2, 4]);
+/// assert_eq!(difference(&a, &b, DefaultOrder), [1, 2]);
+/// ```
+pub fn union<T: Clone, C: Compare<T>>(a: &[T], b: &[T], cmp: C) -> Vec<T> {
+    let mut out = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match cmp.compare(&a[i], &b[j]) {
+            Ordering::Less => {
+                out.push(a[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => {
+                out.push(b[j].clone());
+                j += 1;
+            }
+            Ordering::Equal => {
+                out.push(a[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out.extend_from_slice(&b[j..]);
+    out
+}
+
+/// 有序多重集合的交集：每个元素出现的次数为两边次数的较小值，元素取自 `a`。
+pub fn intersection<T: Clone, C: Compare<T>>(a: &[T], b: &[T], cmp: C) -> Vec<T> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match cmp.compare(&a[i], &b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                out.push(a[i].clone());
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out
+}
+
+/// 有序多重集合的差集 `a - b`：每个元素出现的次数为 `a` 中次数减去 `b` 中次数（不小于 0）。
+pub fn difference<T: Clone, C: Compare<T>>(a: &[T], b: &[T], cmp: C) -> Vec<T> {
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match cmp.compare(&a[i], &b[j]) {
+            Ordering::Less => {
+                out.push(a[i].clone());
+                i += 1;
+            }
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out.extend_from_slice(&a[i..]);
+    out
+}
+
+/// 把多个有序向量归并成一个有序向量，相等的元素按输入向量的顺序排列（稳定）。
+///
+/// # 示例
+///
+/// ```
+/// use vector_sort::sorted::merge_sorted;
+/// use vector_sort::comparator::DefaultOrder;
+///
+/// let merged = merge_sorted(vec![vec![1, 4, 7], vec![2, 5], vec![3, 6, 9]], DefaultOrder);
+///
+/// assert_eq!(merged, [1, 2, 3, 4, 5, 6, 7, 9]);
+/// ```
+pub fn merge_sorted<T, C: Compare<T>>(vecs: Vec<Vec<T>>, cmp: C) -> Vec<T> {
+    let len = vecs.iter().map(Vec::len).sum();
+    let mut out = Vec::with_capacity(len);
+    out.extend(kmerge(vecs, cmp));
+    out
+}
+
+/// 惰性地对多个有序迭代器做 k 路归并，每产出一个元素的时间为 O(log k)。
+///
+/// 相等的元素按输入迭代器的顺序产出，因此归并是稳定的。
+pub fn kmerge<T, I, C>(iters: I, cmp: C) -> KMerge<<I::Item as IntoIterator>::IntoIter, C>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator<Item = T>,
+    C: Compare<T>,
+{
+    let mut sources: Vec<_> = iters.into_iter().map(IntoIterator::into_iter).collect();
+    let mut heads: Vec<(T, usize)> = sources
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, it)| it.next().map(|x| (x, i)))
+        .collect();
+
+    heap::heapify(&mut heads, &|a, b| head_less(&cmp, a, b));
+    KMerge {
+        sources,
+        heads,
+        cmp,
+    }
+}
+
+/// [`kmerge`] 返回的迭代器。
+pub struct KMerge<I: Iterator, C> {
+    sources: Vec<I>,
+    /// 各个来源当前的首元素及来源编号组成的小顶堆
+    heads: Vec<(I::Item, usize)>,
+    cmp: C,
+}
+
+impl<T, I, C> Iterator for KMerge<I, C>
+where
+    I: Iterator<Item = T>,
+    C: Compare<T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.heads.is_empty() {
+            return None;
+        }
+
+        let source = self.heads[0].1;
+        let item = match self.sources[source].next() {
+            Some(next) => std::mem::replace(&mut self.heads[0].0, next),
+            None => self.heads.swap_remove(0).0,
+        };
+
+        let cmp = &self.cmp;
+        heap::sift_down(&mut self.heads, 0, &|a, b| head_less(cmp, a, b));
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sources.iter().map(Iterator::size_hint).fold(
+            (self.heads.len(), Some(self.heads.len())),
+            |(lo, hi), (l, h)| (lo + l, hi.zip(h).map(|(a, b)| a + b)),
+        )
+    }
+}
+
+/// 先按比较器、再按来源编号比较堆中的元素。
+fn head_less<T, C: Compare<T>>(cmp: &C, (x, i): &(T, usize), (y, j): &(T, usize)) -> bool {
+    cmp.compare(x, y).then(i.cmp(j)).is_lt()
+}
+
+// 测试模块
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comparator::{by, DefaultOrder};
+    use rand::{thread_rng, Rng};
+
+    fn sorted_random(len: usize, max: u32) -> Vec<u32> {
+        let mut rng = thread_rng();
+        let mut vec: Vec<u32> = (0..len).map(|_| rng.gen_range(0..max)).collect();
+        vec.sort();
+        vec
+    }
+
+    /// 测试有序性检查
+    #[test]
+    fn sortedness() {
+        assert!(is_sorted_by::<i32, _>(&[], DefaultOrder));
+        assert_eq!(first_unsorted(&[1, 2, 5, 3, 4], DefaultOrder), Some(3));
+        assert!(is_sorted_by(&[3, 2, 1], |a: &i32, b: &i32| b.cmp(a)));
+    }
+
+    /// 测试二分查找的上下界与线性扫描一致
+    #[test]
+    fn bounds_match_linear_scan() {
+        for _ in 0..50 {
+            let vec = sorted_random(100, 20);
+            for probe in 0..22 {
+                let lower = vec.iter().filter(|&&x| x < probe).count();
+                let upper = vec.iter().filter(|&&x| x <= probe).count();
+
+                assert_eq!(lower_bound(&vec, &probe, DefaultOrder), lower);
+                assert_eq!(upper_bound(&vec, &probe, DefaultOrder), upper);
+                assert_eq!(equal_range(&vec, &probe, DefaultOrder), lower..upper);
+                assert_eq!(equal_range_by_key(&vec, &probe, |&x| x), lower..upper);
+            }
+        }
+    }
+
+    /// 测试按键去重保留第一个或最后一个
+    #[test]
+    fn dedup_keep_first_and_last() {
+        let vec = vec![(1, 'a'), (1, 'b'), (1, 'c'), (2, 'd'), (3, 'e'), (3, 'f')];
+
+        let mut first = vec.clone();
+        dedup_by_key(&mut first, |p| p.0, Keep::First);
+        assert_eq!(first, [(1, 'a'), (2, 'd'), (3, 'e')]);
+
+        let mut last = vec.clone();
+        dedup_by_key(&mut last, |p| p.0, Keep::Last);
+        assert_eq!(last, [(1, 'c'), (2, 'd'), (3, 'f')]);
+    }
+
+    /// 测试集合运算与按计数计算的结果一致
+    #[test]
+    fn set_operations_match_counts() {
+        for _ in 0..100 {
+            let a = sorted_random(30, 10);
+            let b = sorted_random(30, 10);
+            let count = |v: &[u32], x: u32| v.iter().filter(|&&y| y == x).count();
+
+            let u = union(&a, &b, DefaultOrder);
+            let i = intersection(&a, &b, DefaultOrder);
+            let d = difference(&a, &b, DefaultOrder);
+
+            assert!(is_sorted_by(&u, DefaultOrder));
+            assert!(is_sorted_by(&i, DefaultOrder));
+            assert!(is_sorted_by(&d, DefaultOrder));
+            for x in 0..10 {
+                let (ca, cb) = (count(&a, x), count(&b, x));
+                assert_eq!(count(&u, x), ca.max(cb));
+                assert_eq!(count(&i, x), ca.min(cb));
+                assert_eq!(count(&d, x), ca.saturating_sub(cb));
+            }
+        }
+    }
+
+    /// 测试 k 路归并的结果有序且稳定
+    #[test]
+    fn kmerge_is_stable() {
+        let sources: Vec<Vec<(u32, usize)>> = (0..7)
+            .map(|s| {
+                sorted_random(s * 10, 5)
+                    .into_iter()
+                    .map(|x| (x, s))
+                    .collect()
+            })
+            .collect();
+
+        let mut expected: Vec<(u32, usize)> = sources.concat();
+        expected.sort_by_key(|p| p.0);
+
+        let iter = kmerge(sources.clone(), by(|p: &(u32, usize)| p.0));
+        assert_eq!(iter.size_hint(), (expected.len(), Some(expected.len())));
+        assert_eq!(iter.collect::<Vec<_>>(), expected);
+
+        let merged = merge_sorted(sources, by(|p: &(u32, usize)| p.0));
+        assert_eq!(merged, expected);
+
+        assert!(merge_sorted(Vec::<Vec<u32>>::new(), DefaultOrder).is_empty());
+    }
+}
@@ -1,17 +1,37 @@
+use crate::collation::Strength;
+use crate::comparator::{by_str, StrOrder};
+use crate::float_sort::{sort_floats, sort_floats_total, NanPlacement};
+use crate::radix_sort::radix_sort;
+
 pub fn sort_integer_vector() {
     let mut vec = vec![1, 5, 10, 2, 15];
 
     vec.sort();
 
     assert_eq!(vec, vec![1, 2, 5, 10, 15]);
+
+    // 以整数 ID 为主的大量数据可以使用基数排序，规模较小时会自动退化为比较排序
+    let mut ids: Vec<u64> = (0..1_000).map(|i| (i * 7919) % 1_000).collect();
+
+    radix_sort(&mut ids);
+
+    assert_eq!(ids, (0..1_000).collect::<Vec<u64>>());
 }
 
 pub fn sort_float_vector() {
     let mut vec = vec![1.1, 1.15, 5.5, 1.123, 2.0];
 
-    vec.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    // 使用 IEEE 754 totalOrder 排序，即使出现 NaN 也不会 panic
+    sort_floats_total(&mut vec);
 
     assert_eq!(vec, vec![1.1, 1.123, 1.15, 2.0, 5.5]);
+
+    // 传感器数据中可能混入 NaN，可以选择把它们放到末尾或直接过滤掉
+    let mut readings = vec![3.5, f64::NAN, -0.0, 0.0, -1.25];
+
+    sort_floats(&mut readings, NanPlacement::Filter);
+
+    assert_eq!(readings, vec![-1.25, -0.0, 0.0, 3.5]);
 }
 
 // 引入 Debug, Eq, Ord, PartialEq, PartialOrd trait 以支持打印、比较和排序操作。
@@ -56,6 +76,8 @@ pub fn sort_struct_vector() {
 
     // 使用自定义的排序规则，通过 sort_by 方法指定一个闭包函数来比较 Person 实例。
     // 此例中按照 age 字段从大到小排序。
+    // 这里演示的就是 sort_by 本身，因此不采纳 clippy 改用 sort_by_key 的建议。
+    #[allow(clippy::unnecessary_sort_by)]
     people.sort_by(|a, b| b.age.cmp(&a.age));
     // 断言自定义排序后的结果是否符合预期
     assert_eq!(
@@ -66,4 +88,35 @@ pub fn sort_struct_vector() {
             Person::new("John".to_string(), 1),
         ]
     );
+
+    // 使用声明式比较器组合多个排序键：先按名字忽略大小写升序，名字相同再按年龄降序。
+    people.push(Person::new("al".to_string(), 30));
+    by_str(|p: &Person| &p.name, StrOrder::CaseInsensitive)
+        .then_desc(|p| p.age)
+        .sort(&mut people);
+    // 断言多键排序后的结果是否符合预期
+    assert_eq!(
+        people,
+        vec![
+            Person::new("Al".to_string(), 60),
+            Person::new("al".to_string(), 30),
+            Person::new("John".to_string(), 1),
+            Person::new("Zoe".to_string(), 25),
+        ]
+    );
+
+    // 字节序会把带重音的 "Émile" 排在 "Zoe" 之后、把 "John10" 排在 "John2" 之前，
+    // 自然排序规则按基本字母比较并把数字按数值比较。
+    people.push(Person::new("Émile".to_string(), 40));
+    people.push(Person::new("John10".to_string(), 10));
+    people.push(Person::new("John2".to_string(), 2));
+    by_str(|p: &Person| &p.name, StrOrder::Natural(Strength::Tertiary))
+        .then_desc(|p| p.age)
+        .sort(&mut people);
+    // 断言自然排序后的结果是否符合预期
+    let names: Vec<&str> = people.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["al", "Al", "Émile", "John", "John2", "John10", "Zoe"]
+    );
 }
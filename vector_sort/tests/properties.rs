@@ -0,0 +1,637 @@
+//! 所有排序接口的性质测试与差分测试。
+//!
+//! 每个排序实现都在多种输入形态（随机、有序、逆序、管风琴形、大量重复等）和长度下
+//! 与标准库的稳定排序对照，检查：
+//!
+//! * 结果有序且与标准库一致，稳定的实现还要求相等元素的顺序完全一致；
+//! * 结果是输入的一个排列，没有丢失或复制元素；
+//! * 比较器中途 panic 时不会重复释放或泄漏元素，原地排序的切片仍是输入的一个排列。
+
+use std::{
+    cmp::Ordering,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicIsize, AtomicUsize, Ordering::Relaxed},
+        Arc, Once,
+    },
+};
+
+use rand::{
+    distributions::{Distribution, Standard},
+    seq::SliceRandom,
+    thread_rng, Rng,
+};
+use serde::{Deserialize, Serialize};
+use vector_sort::{
+    comparator::{by, DefaultOrder},
+    external_sort::ExternalSorter,
+    float_sort::{sort_floats, sort_floats_total, NanPlacement},
+    instrumented::{sort_counted, Algorithm},
+    key_sort::{par_sort_by_cached_key, try_par_sort_by_cached_key, try_sort_by_key},
+    par_sort::{
+        par_merge_sort_by, par_sample_sort_by, par_sort_by, par_sort_by_with_threshold,
+        par_sort_unstable_by, PAR_SORT_THRESHOLD,
+    },
+    permutation::{apply_permutation, argsort, sort_together},
+    radix_sort::{radix_sort, radix_sort_by_key, radix_sort_lsd, radix_sort_msd},
+    select::{partial_sort, top_k},
+    sorted::{kmerge, merge_sorted},
+};
+
+/// 测试用的输入长度，覆盖空输入、各实现的小规模分支以及并行阈值两侧。
+const LENGTHS: [usize; 11] = [
+    0,
+    1,
+    2,
+    3,
+    5,
+    8,
+    16,
+    33,
+    100,
+    1000,
+    PAR_SORT_THRESHOLD + 500,
+];
+
+/// 输入数据的形态，其中大部分是常见排序算法的最坏或特殊情况。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pattern {
+    Random,
+    Sorted,
+    Reversed,
+    /// 先升后降
+    OrganPipe,
+    /// 多段长度为 16 的升序段
+    SawTooth,
+    /// 只有 4 种不同的值
+    FewUnique,
+    AllEqual,
+    /// 有序数据中随机交换少量元素
+    NearlySorted,
+}
+
+const PATTERNS: [Pattern; 8] = [
+    Pattern::Random,
+    Pattern::Sorted,
+    Pattern::Reversed,
+    Pattern::OrganPipe,
+    Pattern::SawTooth,
+    Pattern::FewUnique,
+    Pattern::AllEqual,
+    Pattern::NearlySorted,
+];
+
+// 为 Standard 实现 Distribution trait，以允许随机生成输入形态
+impl Distribution<Pattern> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Pattern {
+        PATTERNS[rng.gen_range(0..PATTERNS.len())]
+    }
+}
+
+impl Pattern {
+    /// 生成指定形态和长度的键。
+    fn keys<R: Rng + ?Sized>(self, len: usize, rng: &mut R) -> Vec<u32> {
+        let n = len as u32;
+        match self {
+            Pattern::Random => (0..len).map(|_| rng.gen()).collect(),
+            Pattern::Sorted => (0..n).collect(),
+            Pattern::Reversed => (0..n).rev().collect(),
+            Pattern::OrganPipe => (0..n / 2).chain((n / 2..n).map(|i| n - 1 - i)).collect(),
+            Pattern::SawTooth => (0..n).map(|i| i % 16).collect(),
+            Pattern::FewUnique => (0..len).map(|_| rng.gen_range(0..4)).collect(),
+            Pattern::AllEqual => vec![7; len],
+            Pattern::NearlySorted => {
+                let mut keys: Vec<u32> = (0..n).collect();
+                for _ in 0..len / 20 + 1 {
+                    if len > 1 {
+                        keys.swap(rng.gen_range(0..len), rng.gen_range(0..len));
+                    }
+                }
+                keys
+            }
+        }
+    }
+}
+
+/// 被排序的元素：按 `key` 比较，`seq` 是它在输入中的位置，用于检查稳定性和排列性质。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Item {
+    key: u32,
+    seq: usize,
+}
+
+fn items(keys: Vec<u32>) -> Vec<Item> {
+    keys.into_iter()
+        .enumerate()
+        .map(|(seq, key)| Item { key, seq })
+        .collect()
+}
+
+fn by_key(a: &Item, b: &Item) -> Ordering {
+    a.key.cmp(&b.key)
+}
+
+type SortFn = Box<dyn Fn(&mut Vec<Item>)>;
+
+/// 一个待测的排序实现。
+struct Sorter {
+    name: String,
+    stable: bool,
+    /// 超过该长度的输入不测试，避免 O(n²) 的实现拖慢测试
+    max_len: usize,
+    sort: SortFn,
+}
+
+impl Sorter {
+    fn new(name: impl Into<String>, stable: bool, sort: impl Fn(&mut Vec<Item>) + 'static) -> Self {
+        Sorter {
+            name: name.into(),
+            stable,
+            max_len: usize::MAX,
+            sort: Box::new(sort),
+        }
+    }
+
+    fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+fn sorters() -> Vec<Sorter> {
+    let mut sorters = vec![
+        Sorter::new("Comparator::sort", true, |v| by(|x: &Item| x.key).sort(v)),
+        Sorter::new("Comparator::sort_unstable", false, |v| {
+            by(|x: &Item| x.key).sort_unstable(v)
+        }),
+        Sorter::new("par_sort_by", true, |v| par_sort_by(v, by_key)),
+        Sorter::new("par_sort_by_with_threshold", true, |v| {
+            par_sort_by_with_threshold(v, &by_key, 8)
+        }),
+        Sorter::new("par_merge_sort_by", true, |v| {
+            par_merge_sort_by(v, &by_key, 64)
+        }),
+        Sorter::new("par_sort_unstable_by", false, |v| {
+            par_sort_unstable_by(v, by_key)
+        }),
+        Sorter::new("par_sample_sort_by", true, |v| {
+            par_sample_sort_by(v, by_key)
+        }),
+        Sorter::new("ExternalSorter::sort_by", true, |v| {
+            *v = ExternalSorter::new()
+                .memory_budget(4096)
+                .sort_by(v.drain(..), by_key)
+                .unwrap()
+                .collect::<std::io::Result<_>>()
+                .unwrap();
+        })
+        .max_len(2000),
+        Sorter::new("radix_sort_by_key", true, |v| {
+            radix_sort_by_key(v, |x| x.key)
+        }),
+        Sorter::new("try_sort_by_key", true, |v| {
+            try_sort_by_key(v, |x| Ok::<_, ()>(x.key)).unwrap()
+        }),
+        Sorter::new("par_sort_by_cached_key", true, |v| {
+            par_sort_by_cached_key(v, |x| x.key)
+        }),
+        Sorter::new("try_par_sort_by_cached_key", true, |v| {
+            try_par_sort_by_cached_key(v, |x| Ok::<_, ()>(x.key)).unwrap()
+        }),
+        Sorter::new("partial_sort", false, |v| {
+            let len = v.len();
+            partial_sort(v, len, by_key)
+        }),
+        Sorter::new("top_k", true, |v| {
+            *v = top_k(v, v.len(), by_key).into_iter().cloned().collect()
+        }),
+        Sorter::new("argsort + apply_permutation", true, |v| {
+            let perm = argsort(v, by_key);
+            apply_permutation(&perm, &mut [v]);
+        }),
+        Sorter::new("sort_together", true, |v| {
+            let mut keys: Vec<u32> = v.iter().map(|x| x.key).collect();
+            sort_together(&mut keys, &mut [v], DefaultOrder);
+        }),
+        Sorter::new("merge_sorted", true, |v| {
+            // 切成若干段分别排序，再归并
+            let runs = v
+                .chunks(37)
+                .map(|chunk| {
+                    let mut run = chunk.to_vec();
+                    run.sort_by(by_key);
+                    run
+                })
+                .collect();
+            *v = merge_sorted(runs, by_key);
+        }),
+        Sorter::new("kmerge", true, |v| {
+            let mut runs: Vec<Vec<Item>> = v.chunks(100).map(<[Item]>::to_vec).collect();
+            for run in &mut runs {
+                run.sort_by(by_key);
+            }
+            *v = kmerge(runs, by_key).collect();
+        }),
+    ];
+
+    for algorithm in Algorithm::ALL {
+        let quadratic = matches!(algorithm, Algorithm::Insertion);
+        let sorter = Sorter::new(
+            format!("instrumented {algorithm}"),
+            algorithm.is_stable(),
+            move |v| {
+                sort_counted(v, algorithm, by_key);
+            },
+        );
+        sorters.push(if quadratic {
+            sorter.max_len(1000)
+        } else {
+            sorter
+        });
+    }
+
+    sorters
+}
+
+/// 对照标准库的稳定排序检查一个实现在一组输入上的结果。
+fn check(sorter: &Sorter, original: &[Item], pattern: Pattern) {
+    let mut expected = original.to_vec();
+    expected.sort_by(by_key);
+
+    let mut actual = original.to_vec();
+    (sorter.sort)(&mut actual);
+
+    let context = format!(
+        "{} on {:?} input of length {}",
+        sorter.name,
+        pattern,
+        original.len()
+    );
+    assert_eq!(actual.len(), original.len(), "{context}: length changed");
+
+    if sorter.stable {
+        if let Some(i) = (0..actual.len()).find(|&i| actual[i] != expected[i]) {
+            panic!(
+                "{context}: differs from std stable sort at {i}: {:?} != {:?}",
+                actual[i], expected[i]
+            );
+        }
+    } else {
+        let keys = |v: &[Item]| v.iter().map(|x| x.key).collect::<Vec<_>>();
+        assert!(
+            keys(&actual) == keys(&expected),
+            "{context}: keys differ from std sort"
+        );
+
+        // 按输入位置还原后应与输入完全一致，即没有丢失或复制元素
+        actual.sort_by_key(|x| x.seq);
+        assert!(
+            actual == original,
+            "{context}: not a permutation of the input"
+        );
+    }
+}
+
+/// 测试所有实现在所有输入形态和长度下与标准库一致
+#[test]
+fn all_sorters_match_std() {
+    let mut rng = thread_rng();
+    let sorters = sorters();
+
+    for pattern in PATTERNS {
+        for len in LENGTHS {
+            let original = items(pattern.keys(len, &mut rng));
+            for sorter in sorters.iter().filter(|s| len <= s.max_len) {
+                check(sorter, &original, pattern);
+            }
+        }
+    }
+}
+
+/// 测试随机选取的形态、长度和取值范围
+#[test]
+fn randomized_inputs() {
+    let mut rng = thread_rng();
+    let sorters = sorters();
+
+    for _ in 0..200 {
+        let pattern: Pattern = rng.gen();
+        let len = rng.gen_range(0..300);
+        // 把键压缩到随机的取值范围内，制造不同程度的重复
+        let modulus = rng.gen_range(1..=len.max(1) as u32);
+        let keys = pattern
+            .keys(len, &mut rng)
+            .into_iter()
+            .map(|k| k % modulus)
+            .collect();
+        let original = items(keys);
+
+        for sorter in &sorters {
+            check(sorter, &original, pattern);
+        }
+    }
+}
+
+/// 测试整数基数排序的各个变体与标准库一致，包括负数和边界值
+#[test]
+fn radix_sorts_match_std() {
+    let mut rng = thread_rng();
+    for pattern in PATTERNS {
+        for len in LENGTHS {
+            let unsigned = pattern.keys(len, &mut rng);
+            // 映射到以 0 为中心的有符号数，并混入边界值
+            let mut signed: Vec<i64> = unsigned
+                .iter()
+                .map(|&k| k as i64 - (len as i64 / 2))
+                .collect();
+            if len > 2 {
+                signed[0] = i64::MIN;
+                signed[len - 1] = i64::MAX;
+            }
+
+            for sort in [
+                radix_sort::<u32>,
+                radix_sort_lsd::<u32>,
+                radix_sort_msd::<u32>,
+            ] {
+                let mut expected = unsigned.clone();
+                expected.sort();
+                let mut actual = unsigned.clone();
+                sort(&mut actual);
+                assert_eq!(actual, expected, "{pattern:?} u32 len {len}");
+            }
+            for sort in [
+                radix_sort::<i64>,
+                radix_sort_lsd::<i64>,
+                radix_sort_msd::<i64>,
+            ] {
+                let mut expected = signed.clone();
+                expected.sort();
+                let mut actual = signed.clone();
+                sort(&mut actual);
+                assert_eq!(actual, expected, "{pattern:?} i64 len {len}");
+            }
+        }
+    }
+}
+
+/// 生成包含 NaN、正负零、无穷大和次正规数的浮点数
+fn special_float<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    const SPECIAL: [f64; 8] = [
+        f64::NAN,
+        -f64::NAN,
+        0.0,
+        -0.0,
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::MIN_POSITIVE / 2.0,
+        f64::MAX,
+    ];
+    if rng.gen_bool(0.2) {
+        *SPECIAL.choose(rng).unwrap()
+    } else {
+        rng.gen_range(-1e6..1e6)
+    }
+}
+
+/// 测试浮点数排序与 total_cmp 一致，以及各种 NaN 放置方式
+#[test]
+fn float_sorts_match_total_cmp() {
+    let mut rng = thread_rng();
+    for len in LENGTHS {
+        let original: Vec<f64> = (0..len).map(|_| special_float(&mut rng)).collect();
+        let bits = |v: &[f64]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+
+        let mut expected = original.clone();
+        expected.sort_by(f64::total_cmp);
+        for sort in [sort_floats_total::<f64>, radix_sort::<f64>] {
+            let mut actual = original.clone();
+            sort(&mut actual);
+            assert_eq!(bits(&actual), bits(&expected), "len {len}");
+        }
+
+        let mut numbers: Vec<f64> = original.iter().copied().filter(|x| !x.is_nan()).collect();
+        numbers.sort_by(f64::total_cmp);
+        let nans = len - numbers.len();
+
+        for nan in [
+            NanPlacement::First,
+            NanPlacement::Last,
+            NanPlacement::Filter,
+        ] {
+            let mut actual = original.clone();
+            sort_floats(&mut actual, nan);
+
+            let (nan_part, number_part) = match nan {
+                NanPlacement::First => actual.split_at(nans),
+                NanPlacement::Last => {
+                    let (numbers, nans) = actual.split_at(len - nans);
+                    (nans, numbers)
+                }
+                NanPlacement::Filter => actual.split_at(0),
+            };
+            assert!(nan_part.iter().all(|x| x.is_nan()), "{nan:?} len {len}");
+            assert_eq!(bits(number_part), bits(&numbers), "{nan:?} len {len}");
+        }
+    }
+}
+
+/// 统计存活实例数量的元素，用于检测比较器 panic 后是否重复释放或泄漏元素。
+#[derive(Debug)]
+struct Tracked {
+    key: u32,
+    live: Arc<AtomicIsize>,
+}
+
+impl Tracked {
+    fn new(key: u32, live: &Arc<AtomicIsize>) -> Self {
+        live.fetch_add(1, Relaxed);
+        Tracked {
+            key,
+            live: Arc::clone(live),
+        }
+    }
+}
+
+impl Clone for Tracked {
+    fn clone(&self) -> Self {
+        Tracked::new(self.key, &self.live)
+    }
+}
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.live.fetch_sub(1, Relaxed);
+    }
+}
+
+/// 测试中故意触发的 panic 信息。
+const EXPECTED_PANIC: &str = "comparator panicked on purpose";
+
+/// 不打印预期中的 panic（其中一部分发生在 rayon 的工作线程上，无法被测试框架捕获），
+/// 其他 panic 仍交给原来的 hook 处理。
+fn silence_expected_panics() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = info
+                .payload()
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| info.payload().downcast_ref::<&str>().copied())
+                .unwrap_or_default();
+            let expected = message == EXPECTED_PANIC
+                || message.contains("does not correctly implement a total order");
+            if !expected {
+                default(info);
+            }
+        }));
+    });
+}
+
+type TrackedCmp<'a> = &'a (dyn Fn(&Tracked, &Tracked) -> Ordering + Sync);
+type PanicSortFn = Box<dyn Fn(&mut Vec<Tracked>, TrackedCmp)>;
+
+/// 一个可能因比较器 panic 而中断的排序实现。
+struct PanicCase {
+    name: String,
+    /// 中断后切片是否仍保证是输入的一个排列；消耗输入的实现只检查元素的释放
+    in_place: bool,
+    sort: PanicSortFn,
+}
+
+fn panic_cases() -> Vec<PanicCase> {
+    fn case(
+        name: &str,
+        in_place: bool,
+        sort: impl Fn(&mut Vec<Tracked>, TrackedCmp) + 'static,
+    ) -> PanicCase {
+        PanicCase {
+            name: name.to_string(),
+            in_place,
+            sort: Box::new(sort),
+        }
+    }
+
+    let mut cases = vec![
+        case("Comparator::sort", true, |v, cmp| {
+            vector_sort::comparator::Comparator::new()
+                .then_with(cmp)
+                .sort(v)
+        }),
+        case("par_sort_by", true, |v, cmp| par_sort_by(v, cmp)),
+        case("par_merge_sort_by", true, |v, cmp| {
+            par_merge_sort_by(v, &cmp, 64)
+        }),
+        case("par_sort_unstable_by", true, |v, cmp| {
+            par_sort_unstable_by(v, cmp)
+        }),
+        case("partial_sort", true, |v, cmp| {
+            let k = v.len() / 2;
+            partial_sort(v, k, cmp)
+        }),
+        case("sort_together", true, |v, cmp| {
+            let mut copy = v.clone();
+            sort_together(v, &mut [&mut copy], cmp);
+        }),
+        case("top_k", true, |v, cmp| {
+            top_k(v, 10, cmp);
+        }),
+        case("par_sample_sort_by", true, |v, cmp| {
+            par_sample_sort_by(v, cmp)
+        }),
+    ];
+
+    for algorithm in Algorithm::ALL {
+        cases.push(PanicCase {
+            name: format!("instrumented {algorithm}"),
+            in_place: true,
+            sort: Box::new(move |v, cmp| {
+                sort_counted(v, algorithm, cmp);
+            }),
+        });
+    }
+
+    cases
+}
+
+/// 测试比较器在不同时刻 panic 时，元素既不会被重复释放也不会泄漏，原地排序不丢失元素
+#[test]
+fn comparator_panic_safety() {
+    let mut rng = thread_rng();
+    silence_expected_panics();
+
+    for case in panic_cases() {
+        for len in [50, 1000, PAR_SORT_THRESHOLD + 500] {
+            for limit in [0, 1, 7, 100, len * 4] {
+                let live = Arc::new(AtomicIsize::new(0));
+                let keys = Pattern::FewUnique.keys(len, &mut rng);
+                let mut vec: Vec<Tracked> = keys.iter().map(|&k| Tracked::new(k, &live)).collect();
+
+                let calls = AtomicUsize::new(0);
+                let cmp = |a: &Tracked, b: &Tracked| {
+                    if calls.fetch_add(1, Relaxed) == limit {
+                        panic!("{EXPECTED_PANIC}");
+                    }
+                    a.key.cmp(&b.key)
+                };
+
+                let result = panic::catch_unwind(AssertUnwindSafe(|| (case.sort)(&mut vec, &cmp)));
+
+                if case.in_place || result.is_ok() {
+                    let mut after: Vec<u32> = vec.iter().map(|t| t.key).collect();
+                    let mut before = keys.clone();
+                    after.sort();
+                    before.sort();
+                    assert!(
+                        after == before,
+                        "{} (len {len}, panic at {limit}): elements lost or duplicated",
+                        case.name
+                    );
+                }
+
+                drop(vec);
+                assert_eq!(
+                    live.load(Relaxed),
+                    0,
+                    "{} (len {len}, panic at {limit}): elements leaked or dropped twice",
+                    case.name
+                );
+            }
+        }
+    }
+}
+
+/// 测试违反全序的比较器不会破坏内存安全：结果可以是任意顺序，也允许 panic，但元素不能丢失
+#[test]
+fn inconsistent_comparator() {
+    silence_expected_panics();
+
+    for case in panic_cases().into_iter().filter(|c| c.in_place) {
+        let live = Arc::new(AtomicIsize::new(0));
+        let mut vec: Vec<Tracked> = (0..2000).map(|k| Tracked::new(k, &live)).collect();
+
+        let cmp = |_: &Tracked, _: &Tracked| {
+            [Ordering::Less, Ordering::Equal, Ordering::Greater][thread_rng().gen_range(0..3)]
+        };
+        // 标准库检测到违反全序时会 panic，这也是允许的结果
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| (case.sort)(&mut vec, &cmp)));
+
+        let mut keys: Vec<u32> = vec.iter().map(|t| t.key).collect();
+        keys.sort();
+        assert!(
+            keys.into_iter().eq(0..2000),
+            "{}: elements lost or duplicated",
+            case.name
+        );
+
+        drop(vec);
+        assert_eq!(
+            live.load(Relaxed),
+            0,
+            "{}: elements leaked or dropped twice",
+            case.name
+        );
+    }
+}